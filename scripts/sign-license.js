@@ -0,0 +1,150 @@
+#!/usr/bin/env node
+/**
+ * 人脸活体检测系统 - 离线授权文件签发脚本
+ *
+ * 功能：
+ * 1. 生成 Ed25519 密钥对（keygen）
+ * 2. 使用私钥签发离线授权文件（sign）
+ * 3. 使用测试密钥重新生成 src-tauri/license/fixtures 下的测试文件（fixtures）
+ *
+ * 用法：
+ *   node scripts/sign-license.js keygen <输出目录>
+ *   node scripts/sign-license.js sign --key <私钥.pem> --api-key <API Key> [--expires-at <时间>]
+ *        [--customer <客户名称>] [--base-url <服务器地址>] [--license-id <授权编号>] [--out <文件>]
+ *   node scripts/sign-license.js fixtures
+ */
+
+import { generateKeyPairSync, createPrivateKey, sign, randomUUID } from 'crypto';
+import { writeFileSync, readFileSync, mkdirSync } from 'fs';
+import { fileURLToPath } from 'url';
+import { dirname, join } from 'path';
+
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = dirname(__filename);
+const PROJECT_DIR = dirname(__dirname);
+const LICENSE_DIR = join(PROJECT_DIR, 'src-tauri', 'license');
+
+// 授权文件格式版本（与 src-tauri/src/license_file.rs 保持一致）
+const LICENSE_VERSION = 1;
+
+// 生成密钥对：私钥为 PKCS#8 PEM，公钥为 32 字节原始公钥的 Base64
+function keygen(outDir) {
+  const { publicKey, privateKey } = generateKeyPairSync('ed25519');
+  mkdirSync(outDir, { recursive: true });
+  writeFileSync(join(outDir, 'private.pem'), privateKey.export({ type: 'pkcs8', format: 'pem' }));
+  const raw = Buffer.from(publicKey.export({ format: 'jwk' }).x, 'base64url');
+  writeFileSync(join(outDir, 'public.key'), raw.toString('base64') + '\n');
+  console.log(`密钥对已生成：${outDir}`);
+  console.log('公钥需在构建时通过 LICENSE_PUBLIC_KEY 环境变量嵌入程序，私钥请妥善保管');
+}
+
+// 签名授权内容，返回授权文件对象
+function signLicense(privateKeyPem, payload) {
+  const key = createPrivateKey(privateKeyPem);
+  const payloadBytes = Buffer.from(JSON.stringify(payload), 'utf-8');
+  const signature = sign(null, payloadBytes, key);
+  return {
+    payload: payloadBytes.toString('base64'),
+    signature: signature.toString('base64'),
+  };
+}
+
+function buildPayload({ apiKey, expiresAt, customer, baseUrl, licenseId }) {
+  const payload = {
+    version: LICENSE_VERSION,
+    licenseId: licenseId || randomUUID(),
+    customer: customer || '',
+    apiKey,
+    issuedAt: new Date().toISOString(),
+  };
+  if (expiresAt) payload.expiresAt = expiresAt;
+  if (baseUrl) payload.baseUrl = baseUrl;
+  return payload;
+}
+
+function writeLicense(file, license) {
+  writeFileSync(file, JSON.stringify(license, null, 2) + '\n');
+  console.log(`授权文件已生成：${file}`);
+}
+
+function parseArgs(args) {
+  const options = {};
+  for (let i = 0; i < args.length; i += 2) {
+    const name = args[i].replace(/^--/, '').replace(/-([a-z])/g, (_, c) => c.toUpperCase());
+    options[name] = args[i + 1];
+  }
+  return options;
+}
+
+// 使用测试密钥生成测试文件
+function fixtures() {
+  const testKey = readFileSync(join(LICENSE_DIR, 'test_private.pem'), 'utf-8');
+  const dir = join(LICENSE_DIR, 'fixtures');
+  mkdirSync(dir, { recursive: true });
+
+  const common = { customer: '测试客户', apiKey: 'sk_offline_test_0123456789abcdef' };
+
+  // 有效期至 2099 年
+  writeLicense(join(dir, 'valid.license.json'), signLicense(testKey, {
+    ...buildPayload({ ...common, licenseId: 'TEST-VALID', expiresAt: '2099-12-31T23:59:59Z' }),
+    issuedAt: '2026-01-01T00:00:00Z',
+  }));
+
+  // 长期有效
+  writeLicense(join(dir, 'perpetual.license.json'), signLicense(testKey, {
+    ...buildPayload({ ...common, licenseId: 'TEST-PERPETUAL' }),
+    issuedAt: '2026-01-01T00:00:00Z',
+  }));
+
+  // 已过期
+  writeLicense(join(dir, 'expired.license.json'), signLicense(testKey, {
+    ...buildPayload({ ...common, licenseId: 'TEST-EXPIRED', expiresAt: '2020-01-01T00:00:00Z' }),
+    issuedAt: '2019-01-01T00:00:00Z',
+  }));
+
+  // 绑定其他服务器地址
+  writeLicense(join(dir, 'other-server.license.json'), signLicense(testKey, {
+    ...buildPayload({ ...common, licenseId: 'TEST-OTHER-SERVER', expiresAt: '2099-12-31T23:59:59Z', baseUrl: 'http://10.0.0.1:8000' }),
+    issuedAt: '2026-01-01T00:00:00Z',
+  }));
+
+  // 签名后篡改内容（延长有效期）
+  const tampered = signLicense(testKey, {
+    ...buildPayload({ ...common, licenseId: 'TEST-TAMPERED', expiresAt: '2020-01-01T00:00:00Z' }),
+    issuedAt: '2019-01-01T00:00:00Z',
+  });
+  const payload = JSON.parse(Buffer.from(tampered.payload, 'base64').toString('utf-8'));
+  payload.expiresAt = '2099-12-31T23:59:59Z';
+  tampered.payload = Buffer.from(JSON.stringify(payload), 'utf-8').toString('base64');
+  writeLicense(join(dir, 'tampered.license.json'), tampered);
+
+  // 使用其他私钥签名
+  const { privateKey } = generateKeyPairSync('ed25519');
+  writeLicense(join(dir, 'wrong-key.license.json'), signLicense(
+    privateKey.export({ type: 'pkcs8', format: 'pem' }),
+    { ...buildPayload({ ...common, licenseId: 'TEST-WRONG-KEY', expiresAt: '2099-12-31T23:59:59Z' }), issuedAt: '2026-01-01T00:00:00Z' },
+  ));
+}
+
+const [command, ...rest] = process.argv.slice(2);
+switch (command) {
+  case 'keygen':
+    keygen(rest[0] || '.');
+    break;
+  case 'sign': {
+    const options = parseArgs(rest);
+    if (!options.key || !options.apiKey) {
+      console.error('缺少 --key 或 --api-key 参数');
+      process.exit(1);
+    }
+    const license = signLicense(readFileSync(options.key, 'utf-8'), buildPayload(options));
+    writeLicense(options.out || 'license.json', license);
+    break;
+  }
+  case 'fixtures':
+    fixtures();
+    break;
+  default:
+    console.log('用法：node scripts/sign-license.js <keygen|sign|fixtures> [参数]');
+    process.exit(1);
+}
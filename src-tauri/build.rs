@@ -1,3 +1,5 @@
 fn main() {
+    // 离线授权公钥在构建时嵌入
+    println!("cargo:rerun-if-env-changed=LICENSE_PUBLIC_KEY");
     tauri_build::build()
 }
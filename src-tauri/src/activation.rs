@@ -0,0 +1,241 @@
+use serde::Serialize;
+use std::fmt;
+
+/// 激活码前缀
+const PREFIX: &str = "ACT";
+/// 前缀之后两组字符的长度（ACT-XXXXXXXX-XXXXXXXX）
+const GROUP_LEN: usize = 8;
+const BODY_LEN: usize = GROUP_LEN * 2;
+
+/// 激活码被拒绝的原因（序列化值供前端区分提示）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum ActivationCodeError {
+    /// 未输入激活码
+    Empty,
+    /// 不以 ACT 开头
+    MissingPrefix,
+    /// 含有字母数字以外的字符（position 为去掉前缀和分隔符后的位置，从 1 开始）
+    #[serde(rename_all = "camelCase")]
+    InvalidCharacter { character: char, position: usize },
+    /// 前缀之后的字符数不足 16 位
+    #[serde(rename_all = "camelCase")]
+    TooShort { length: usize },
+    /// 前缀之后的字符数超过 16 位
+    #[serde(rename_all = "camelCase")]
+    TooLong { length: usize },
+    /// 分隔符位置不符合 ACT-XXXXXXXX-XXXXXXXX
+    MalformedGroups,
+}
+
+impl fmt::Display for ActivationCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivationCodeError::Empty => write!(f, "请输入激活码"),
+            ActivationCodeError::MissingPrefix => write!(f, "激活码必须以 ACT- 开头"),
+            ActivationCodeError::InvalidCharacter { character, position } => {
+                write!(f, "激活码第 {} 位含有无效字符“{}”，只允许字母和数字", position, character)
+            }
+            ActivationCodeError::TooShort { length } => {
+                write!(f, "激活码位数不足：需要 {} 位，实际 {} 位", BODY_LEN, length)
+            }
+            ActivationCodeError::TooLong { length } => {
+                write!(f, "激活码位数过多：需要 {} 位，实际 {} 位", BODY_LEN, length)
+            }
+            ActivationCodeError::MalformedGroups => {
+                write!(f, "激活码分段格式不正确，应为 ACT-XXXXXXXX-XXXXXXXX")
+            }
+        }
+    }
+}
+
+/// 解析后的激活码（已规范化为 ACT-XXXXXXXX-XXXXXXXX）
+///
+/// 文档约定的格式不包含校验位，因此只做格式校验，有效性由后端判断。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivationCode(String);
+
+impl ActivationCode {
+    /// 解析用户输入的激活码
+    ///
+    /// 忽略首尾及中间的空白（含全角空格），将全角字母数字转换为半角、
+    /// 各类破折号（全角、长短破折号、减号等）视为连字符，并统一转为大写。
+    /// 分隔符可以省略，但出现时必须位于规定位置。
+    pub fn parse(input: &str) -> Result<Self, ActivationCodeError> {
+        let normalized: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(normalize_char)
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        if normalized.is_empty() {
+            return Err(ActivationCodeError::Empty);
+        }
+
+        let rest = normalized
+            .strip_prefix(PREFIX)
+            .ok_or(ActivationCodeError::MissingPrefix)?;
+
+        let body: String = rest.chars().filter(|c| *c != '-').collect();
+        if let Some((index, character)) = body
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_alphanumeric())
+        {
+            return Err(ActivationCodeError::InvalidCharacter {
+                character,
+                position: index + 1,
+            });
+        }
+
+        let length = body.chars().count();
+        if length < BODY_LEN {
+            return Err(ActivationCodeError::TooShort { length });
+        }
+        if length > BODY_LEN {
+            return Err(ActivationCodeError::TooLong { length });
+        }
+
+        // 分隔符可以全部省略；出现时只能是 "-XXXXXXXX-XXXXXXXX" 中的位置
+        if rest.contains('-') && !has_valid_separators(rest) {
+            return Err(ActivationCodeError::MalformedGroups);
+        }
+
+        Ok(ActivationCode(format!(
+            "{}-{}-{}",
+            PREFIX,
+            &body[..GROUP_LEN],
+            &body[GROUP_LEN..]
+        )))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 日志中使用的脱敏形式（只保留第一组的前 4 位）
+    pub fn masked(&self) -> String {
+        format!("{}-{}****-********", PREFIX, &self.0[4..8])
+    }
+}
+
+/// 将常见的粘贴产物转换为 ASCII 字符
+fn normalize_char(c: char) -> char {
+    match c {
+        // 连字符、破折号、减号及其全角形式
+        '\u{2010}'..='\u{2015}' | '\u{2212}' | '\u{FE58}' | '\u{FE63}' | '\u{FF0D}' | '\u{30FC}' => '-',
+        // 全角数字和字母
+        '\u{FF10}'..='\u{FF19}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}' => {
+            char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// 检查前缀之后的分隔符是否符合 "-XXXXXXXX-XXXXXXXX"（允许只省略其中一个）
+fn has_valid_separators(rest: &str) -> bool {
+    let rest = rest.strip_prefix('-').unwrap_or(rest);
+    match rest.split_once('-') {
+        Some((first, second)) => first.len() == GROUP_LEN && !second.contains('-'),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<String, ActivationCodeError> {
+        ActivationCode::parse(input).map(|code| code.as_str().to_string())
+    }
+
+    #[test]
+    fn parses_canonical_code() {
+        assert_eq!(parse("ACT-ABCD1234-EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("act-abcd1234-efgh5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+    }
+
+    #[test]
+    fn accepts_full_width_and_em_dashes() {
+        assert_eq!(parse("ACT－ABCD1234－EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACT—ABCD1234—EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACT–ABCD1234‐EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACT−ABCD1234ーEFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+    }
+
+    #[test]
+    fn accepts_full_width_letters_and_digits() {
+        assert_eq!(parse("ＡＣＴ-ａｂｃｄ１２３４-ＥＦＧＨ５６７８").unwrap(), "ACT-ABCD1234-EFGH5678");
+    }
+
+    #[test]
+    fn ignores_inner_whitespace() {
+        assert_eq!(parse("  ACT - ABCD 1234 -\tEFGH5678\n").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACT\u{3000}ABCD1234\u{3000}EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+    }
+
+    #[test]
+    fn accepts_missing_separators() {
+        assert_eq!(parse("ACTABCD1234EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACT-ABCD1234EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+        assert_eq!(parse("ACTABCD1234-EFGH5678").unwrap(), "ACT-ABCD1234-EFGH5678");
+    }
+
+    #[test]
+    fn rejects_misplaced_separators() {
+        for input in [
+            "ACT-ABCD-1234EFGH5678",
+            "ACT-ABCD1234E-FGH5678",
+            "ACT-ABCD1234-EFGH-5678",
+            "ACT--ABCD1234-EFGH5678",
+            "ACT-ABCD1234--EFGH5678",
+            "ACT-ABCD1234-EFGH5678-",
+        ] {
+            assert_eq!(parse(input), Err(ActivationCodeError::MalformedGroups), "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_and_missing_prefix() {
+        assert_eq!(parse(""), Err(ActivationCodeError::Empty));
+        assert_eq!(parse(" \u{3000} "), Err(ActivationCodeError::Empty));
+        assert_eq!(parse("ABC-ABCD1234-EFGH5678"), Err(ActivationCodeError::MissingPrefix));
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        assert_eq!(
+            parse("ACT-ABCD1234-EFGH567_"),
+            Err(ActivationCodeError::InvalidCharacter { character: '_', position: 16 })
+        );
+        assert_eq!(
+            parse("ACT-ABCDé234-EFGH5678"),
+            Err(ActivationCodeError::InvalidCharacter { character: 'é', position: 5 })
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        assert_eq!(parse("ACT-ABCD1234-EFGH567"), Err(ActivationCodeError::TooShort { length: 15 }));
+        assert_eq!(parse("ACT-ABCD1234-EFGH56789"), Err(ActivationCodeError::TooLong { length: 17 }));
+    }
+
+    #[test]
+    fn short_inputs_do_not_panic() {
+        assert_eq!(parse("A"), Err(ActivationCodeError::MissingPrefix));
+        assert_eq!(parse("AC"), Err(ActivationCodeError::MissingPrefix));
+        assert_eq!(parse("ACT"), Err(ActivationCodeError::TooShort { length: 0 }));
+        assert_eq!(parse("ACT-"), Err(ActivationCodeError::TooShort { length: 0 }));
+        assert_eq!(parse("ACT-1"), Err(ActivationCodeError::TooShort { length: 1 }));
+        assert_eq!(parse("ACT-é"), Err(ActivationCodeError::InvalidCharacter { character: 'é', position: 1 }));
+        assert_eq!(parse("ACT-AB"), Err(ActivationCodeError::TooShort { length: 2 }));
+    }
+
+    #[test]
+    fn masks_all_but_first_four_characters() {
+        let code = ActivationCode::parse("act abcd1234 efgh5678").unwrap();
+        assert_eq!(code.masked(), "ACT-ABCD****-********");
+        assert!(!code.masked().contains("1234"));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::api::{
+    ActivationCodeCreateParams, ActivationCodeListParams, ActivationCodeUpdateParams, ApiClient,
+    BackendActivationCode, BackendActivationCodeCreateResponse,
+    BackendActivationCodeListResponse, BackendQueueStatusResponse,
+};
+use crate::error::AppError;
+use crate::profiles::ProfileState;
+use crate::tasks::now_millis;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+/// 提前视为过期的时间，避免请求途中 JWT 失效
+const EXPIRY_MARGIN_MS: u64 = 30_000;
+
+/// 管理员会话（JWT 只保存在内存中，不写入磁盘或密钥环）
+struct AdminSession {
+    token: String,
+    username: String,
+    /// 登录时的服务器配置，切换服务器后会话失效
+    profile_id: String,
+    /// 过期时间（Unix 毫秒），为空表示后端未提供有效期
+    expires_at: Option<u64>,
+}
+
+impl AdminSession {
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| now_millis() + EXPIRY_MARGIN_MS >= expires_at)
+    }
+
+    fn info(&self) -> AdminSessionInfo {
+        AdminSessionInfo {
+            username: self.username.clone(),
+            profile_id: self.profile_id.clone(),
+            expires_at: self.expires_at,
+            remaining_secs: self
+                .expires_at
+                .map(|expires_at| expires_at.saturating_sub(now_millis()) / 1000),
+        }
+    }
+}
+
+/// 管理员会话信息（返回给前端，不包含 JWT）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSessionInfo {
+    pub username: String,
+    pub profile_id: String,
+    pub expires_at: Option<u64>,
+    pub remaining_secs: Option<u64>,
+}
+
+/// 管理员会话状态（用于 Tauri State）
+#[derive(Default)]
+pub struct AdminState(Mutex<Option<AdminSession>>);
+
+impl AdminState {
+    /// 创建携带 JWT 的客户端，未登录、已过期或已切换服务器时返回 Unauthorized
+    fn client(&self, app: &AppHandle, profiles: &ProfileState) -> Result<ApiClient, AppError> {
+        let mut session = self.0.lock().unwrap();
+        let active_profile_id = profiles.active_profile().id;
+        let token = match session.as_ref() {
+            None => return Err(AppError::unauthorized("管理员未登录")),
+            Some(s) if s.profile_id != active_profile_id => {
+                *session = None;
+                return Err(AppError::unauthorized("已切换服务器，请重新登录管理员账号"));
+            }
+            Some(s) if s.is_expired() => {
+                log::info!("管理员会话已过期（用户：{}）", s.username);
+                *session = None;
+                let _ = app.emit("admin_session_expired", ());
+                return Err(AppError::unauthorized("管理员会话已过期，请重新登录"));
+            }
+            Some(s) => s.token.clone(),
+        };
+        Ok(profiles.public_client().with_bearer_token(token))
+    }
+
+    /// 后端拒绝 JWT 时清除会话
+    fn check<T>(&self, app: &AppHandle, result: Result<T, AppError>) -> Result<T, AppError> {
+        if let Err(ref e) = result {
+            if e.is_auth_error() && self.0.lock().unwrap().take().is_some() {
+                log::warn!("管理员 JWT 被后端拒绝，已清除会话：{}", e);
+                let _ = app.emit("admin_session_expired", ());
+            }
+        }
+        result
+    }
+}
+
+/// 从 JWT 的 exp 声明读取过期时间（Unix 毫秒），不校验签名
+fn jwt_expiry(token: &str) -> Option<u64> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims.get("exp")?.as_u64().map(|exp| exp * 1000)
+}
+
+// ===== 会话 =====
+
+/// 管理员登录（POST /auth/token），JWT 保存在内存中
+#[tauri::command]
+pub async fn admin_login(
+    username: String,
+    password: String,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<AdminSessionInfo, AppError> {
+    if username.trim().is_empty() || password.is_empty() {
+        return Err(AppError::validation("请输入管理员用户名和密码"));
+    }
+
+    let response = profiles
+        .public_client()
+        .admin_token(username.trim(), &password)
+        .await?;
+
+    let expires_at = response
+        .expires_in
+        .map(|secs| now_millis() + secs * 1000)
+        .or_else(|| jwt_expiry(&response.access_token));
+    let session = AdminSession {
+        token: response.access_token,
+        username: username.trim().to_string(),
+        profile_id: profiles.active_profile().id,
+        expires_at,
+    };
+    let info = session.info();
+    log::info!("管理员 {} 登录成功，过期时间：{:?}", info.username, expires_at);
+
+    *admin.0.lock().unwrap() = Some(session);
+    Ok(info)
+}
+
+/// 退出管理员登录
+#[tauri::command]
+pub fn admin_logout(admin: State<'_, AdminState>) -> Result<(), AppError> {
+    if let Some(session) = admin.0.lock().unwrap().take() {
+        log::info!("管理员 {} 已退出登录", session.username);
+    }
+    Ok(())
+}
+
+/// 获取当前管理员会话（未登录或已过期时返回 null）
+#[tauri::command]
+pub fn get_admin_session(
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<Option<AdminSessionInfo>, AppError> {
+    let active_profile_id = profiles.active_profile().id;
+    let session = admin.0.lock().unwrap();
+    Ok(session
+        .as_ref()
+        .filter(|s| s.profile_id == active_profile_id && !s.is_expired())
+        .map(AdminSession::info))
+}
+
+// ===== 激活码管理 =====
+
+/// 生成激活码
+#[tauri::command]
+pub async fn create_activation_codes(
+    app: AppHandle,
+    params: ActivationCodeCreateParams,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<BackendActivationCodeCreateResponse, AppError> {
+    if params.count == Some(0) {
+        return Err(AppError::validation("生成数量必须大于 0"));
+    }
+    let client = admin.client(&app, &profiles)?;
+    admin.check(&app, client.create_activation_codes(&params).await)
+}
+
+/// 查询激活码列表
+#[tauri::command]
+pub async fn list_activation_codes(
+    app: AppHandle,
+    params: Option<ActivationCodeListParams>,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<BackendActivationCodeListResponse, AppError> {
+    let client = admin.client(&app, &profiles)?;
+    let params = params.unwrap_or_default();
+    admin.check(&app, client.list_activation_codes(&params).await)
+}
+
+/// 修改激活码（有效期、使用次数、备注）
+#[tauri::command]
+pub async fn update_activation_code(
+    app: AppHandle,
+    code: String,
+    params: ActivationCodeUpdateParams,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<BackendActivationCode, AppError> {
+    let client = admin.client(&app, &profiles)?;
+    admin.check(&app, client.update_activation_code(code.trim(), &params).await)
+}
+
+/// 吊销激活码
+#[tauri::command]
+pub async fn revoke_activation_code(
+    app: AppHandle,
+    code: String,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<(), AppError> {
+    let client = admin.client(&app, &profiles)?;
+    admin.check(&app, client.revoke_activation_code(code.trim()).await)?;
+    log::info!("激活码已吊销：{}", code.trim());
+    Ok(())
+}
+
+// ===== 推理队列 =====
+
+/// 查询推理队列状态
+#[tauri::command]
+pub async fn get_queue_status(
+    app: AppHandle,
+    profiles: State<'_, ProfileState>,
+    admin: State<'_, AdminState>,
+) -> Result<BackendQueueStatusResponse, AppError> {
+    let client = admin.client(&app, &profiles)?;
+    admin.check(&app, client.queue_status().await)
+}
@@ -0,0 +1,921 @@
+use crate::error::AppError;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Body, Client, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// API Key 请求头
+pub const API_KEY_HEADER: &str = "X-API-Key";
+/// 管理员 JWT 认证头
+pub const AUTHORIZATION_HEADER: &str = "Authorization";
+/// 客户端 ID 请求头（用于关联 WebSocket 推送）
+pub const CLIENT_ID_HEADER: &str = "X-Client-Id";
+
+/// 默认后端地址
+const DEFAULT_API_BASE_URL: &str = "http://localhost:8000";
+
+/// 从环境变量读取后端地址，未配置时使用本地开发服务器
+pub fn default_base_url() -> String {
+    std::env::var("API_BASE_URL").unwrap_or_else(|_| DEFAULT_API_BASE_URL.to_string())
+}
+
+// ===== 推理请求/响应 =====
+
+/// 单模态检测请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleModeRequest {
+    pub mode: String,
+    pub modality: String,
+    pub images: Vec<String>,
+}
+
+/// 融合模式请求中的图像对
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePair {
+    pub rgb: String,
+    pub ir: String,
+}
+
+/// 融合模式检测请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionModeRequest {
+    pub mode: String,
+    pub pairs: Vec<ImagePair>,
+}
+
+/// 异步任务响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AsyncTaskResponse {
+    pub task_id: String,
+    pub message: String,
+}
+
+/// 检测结果项（后端原始格式）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendDetectionResultItem {
+    pub mode: String,
+    pub result: String,
+    pub confidence: f64,
+    pub probabilities: Vec<f64>,
+    pub processing_time: u64,
+    pub error: Option<String>,
+    pub image_index: Option<u32>,
+}
+
+/// 任务状态响应（GET /infer/task/{task_id}）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendTaskStatusResponse {
+    pub task_id: String,
+    /// pending / running / completed / partial_failure / failed / cancelled
+    pub status: String,
+    pub total_items: u32,
+    #[serde(alias = "processed_items")]
+    pub completed_items: u32,
+    pub message: Option<String>,
+    #[serde(alias = "completed_results")]
+    pub results: Vec<BackendDetectionResultItem>,
+}
+
+impl BackendTaskStatusResponse {
+    /// 任务是否已结束（不会再有新的结果）
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "completed" | "partial_failure" | "failed" | "cancelled"
+        )
+    }
+}
+
+/// 活跃任务摘要（GET /infer/tasks 列表项）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendTaskSummary {
+    pub task_id: String,
+    pub mode: Option<String>,
+    pub status: String,
+    pub total_items: u32,
+    #[serde(alias = "processed_items")]
+    pub completed_items: u32,
+    pub client_id: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// 活跃任务列表响应（GET /infer/tasks）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendTaskListResponse {
+    pub total: u32,
+    #[serde(alias = "items")]
+    pub tasks: Vec<BackendTaskSummary>,
+}
+
+// ===== 激活码 =====
+
+/// 激活码验证请求（发送到后端）
+#[derive(Debug, Serialize)]
+pub struct BackendActivateRequest {
+    pub code: String,
+}
+
+/// 激活码验证响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendActivateResponse {
+    pub api_key: String,
+    pub message: String,
+    pub expires_at: Option<String>,
+}
+
+// ===== 管理员 =====
+
+/// 管理员登录响应（POST /auth/token）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendTokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub token_type: Option<String>,
+    /// 有效期（秒），未返回时从 JWT 的 exp 声明读取
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+}
+
+/// 激活码信息（从后端返回）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendActivationCode {
+    pub code: String,
+    /// unused / used / expired / revoked
+    pub status: String,
+    pub note: Option<String>,
+    pub max_uses: Option<u32>,
+    #[serde(alias = "use_count")]
+    pub used_count: u32,
+    pub created_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub used_at: Option<String>,
+}
+
+/// 激活码列表响应（GET /auth/activation-codes）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendActivationCodeListResponse {
+    pub total: u32,
+    #[serde(alias = "codes")]
+    pub items: Vec<BackendActivationCode>,
+}
+
+/// 批量生成激活码响应（POST /auth/activation-codes）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendActivationCodeCreateResponse {
+    #[serde(alias = "items")]
+    pub codes: Vec<BackendActivationCode>,
+    pub message: Option<String>,
+}
+
+/// 生成激活码参数（前端传入 camelCase，发送到后端时为 snake_case）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ActivationCodeCreateParams {
+    /// 生成数量，默认 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// 激活后的有效天数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_days: Option<u32>,
+    /// 激活码本身的过期时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// 修改激活码参数（只发送提供的字段）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ActivationCodeUpdateParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// 激活码列表查询参数
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationCodeListParams {
+    pub status: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+/// 推理队列状态（GET /infer/queue/status）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendQueueStatusResponse {
+    #[serde(alias = "queue_size", alias = "queue_length")]
+    pub pending: u32,
+    #[serde(alias = "running")]
+    pub processing: u32,
+    #[serde(alias = "workers", alias = "max_concurrent")]
+    pub max_workers: Option<u32>,
+    /// 其余字段原样保留
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+// ===== 图片存储 =====
+
+/// 图片上传请求（发送到后端）
+#[derive(Debug, Serialize)]
+pub struct BackendImageUploadRequest {
+    /// Base64 编码的图片数据
+    pub image: String,
+    pub filename: Option<String>,
+    pub task_id: Option<String>,
+    pub image_index: Option<u32>,
+    pub modality: Option<String>,
+}
+
+/// 已存储的图片信息（从后端返回）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendStoredImage {
+    #[serde(alias = "id")]
+    pub image_id: String,
+    pub task_id: Option<String>,
+    pub image_index: Option<u32>,
+    /// 融合模式下为 rgb / ir
+    pub modality: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub size: u64,
+    pub created_at: Option<String>,
+}
+
+/// 图片列表响应（GET /storage/images）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendStoredImageListResponse {
+    pub total: u32,
+    #[serde(alias = "images")]
+    pub items: Vec<BackendStoredImage>,
+}
+
+/// 图片列表查询参数
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredImageListParams {
+    pub task_id: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+// ===== 健康检查 =====
+
+/// 健康检查响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendHealthResponse {
+    /// "ok" / "healthy" 表示正常，其余视为降级
+    pub status: String,
+    pub version: Option<String>,
+}
+
+impl BackendHealthResponse {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self.status.to_ascii_lowercase().as_str(), "ok" | "healthy" | "up")
+    }
+}
+
+// ===== 历史记录 =====
+
+/// 历史查询参数
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQueryParams {
+    pub client_id: Option<String>,
+    pub mode: Option<String>,
+    pub status: Option<String>,
+    pub days: Option<String>,
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+/// 历史统计参数
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatsParams {
+    pub client_id: Option<String>,
+    pub days: Option<String>,
+}
+
+/// 历史删除参数
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryDeleteParams {
+    pub task_ids: Option<Vec<String>>,
+    pub days_ago: Option<u32>,
+}
+
+/// 历史结果项（从后端返回）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendHistoryResultItem {
+    pub mode: String,
+    pub modality: Option<String>,
+    pub result: String,
+    pub confidence: f64,
+    pub probabilities: Vec<f64>,
+    pub processing_time: u64,
+    pub image_index: Option<u32>,
+    pub error: Option<String>,
+    pub retry_count: u32,
+}
+
+/// 历史任务项（从后端返回）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendHistoryTaskItem {
+    pub task_id: String,
+    pub client_id: Option<String>,
+    pub api_key_hash: Option<String>,
+    pub mode: String,
+    pub status: String,
+    pub total_items: u32,
+    pub successful_items: u32,
+    pub failed_items: u32,
+    pub real_count: u32,
+    pub fake_count: u32,
+    pub elapsed_time_ms: u64,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub results: Option<Vec<BackendHistoryResultItem>>,
+}
+
+/// 历史查询响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendHistoryQueryResponse {
+    pub total: u32,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+    pub items: Vec<BackendHistoryTaskItem>,
+}
+
+/// 历史统计响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendHistoryStatsResponse {
+    pub total_tasks: u32,
+    pub total_inferences: u32,
+    pub total_real: u32,
+    pub total_fake: u32,
+    pub total_errors: u32,
+    pub success_rate: f64,
+    pub avg_processing_time_ms: f64,
+    pub date_range: Option<BackendDateRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BackendDateRange {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+/// 历史删除响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendHistoryDeleteResponse {
+    pub deleted_count: u32,
+    pub message: String,
+}
+
+/// 获取所有历史记录响应（从后端返回）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendHistoryAllResponse {
+    pub total: u32,
+    pub api_key_hash: String,
+    pub items: Vec<BackendHistoryTaskItem>,
+}
+
+// ===== 客户端 =====
+
+/// 后端 API 客户端
+///
+/// 持有后端地址与 API Key，每个后端接口对应一个方法，
+/// 负责拼接 URL、注入认证头、检查状态码并解析响应。
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    http: Client,
+    base_url: String,
+    api_key: Option<String>,
+    /// 管理员 JWT（仅管理员接口使用）
+    bearer_token: Option<String>,
+    /// 携带 API Key 的请求返回 401/403 时上报给授权管理（附带 API Key 所属的服务器配置 ID）
+    auth_errors: Option<(String, UnboundedSender<(String, AppError)>)>,
+}
+
+/// 解析 Retry-After 响应头（秒数或 HTTP 日期），返回需要等待的秒数
+fn retry_after_secs(response: &Response) -> Option<u64> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.timestamp() - chrono::Utc::now().timestamp()).max(0) as u64)
+}
+
+impl ApiClient {
+    pub fn new(http: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            bearer_token: None,
+            auth_errors: None,
+        }
+    }
+
+    /// 设置请求使用的 API Key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// 设置管理员接口使用的 JWT
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// 设置认证失败上报通道，`profile_id` 为 API Key 所属的服务器配置
+    pub fn with_auth_error_reporter(
+        mut self,
+        profile_id: impl Into<String>,
+        sender: UnboundedSender<(String, AppError)>,
+    ) -> Self {
+        self.auth_errors = Some((profile_id.into(), sender));
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 拼接完整的接口地址
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// 拼接以 ID 结尾的接口地址，ID 作为单个路径段编码，其中的 `/`、`?`、`#` 不会改变请求的接口
+    fn url_with_id(&self, path: &str, id: &str) -> Result<String, AppError> {
+        let mut url = Url::parse(&self.url(path))
+            .map_err(|e| AppError::config(format!("服务器地址无效（{}）：{}", self.base_url, e)))?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::config(format!("服务器地址无效：{}", self.base_url)))?
+            .pop_if_empty()
+            .push(id);
+        Ok(url.into())
+    }
+
+    /// WebSocket 推送地址（API Key 通过查询参数认证）
+    pub fn ws_url(&self) -> String {
+        let base = self
+            .base_url
+            .replace("http://", "ws://")
+            .replace("https://", "wss://");
+        match self.api_key {
+            Some(ref key) => format!("{}/infer/ws?api_key={}", base, key),
+            None => format!("{}/infer/ws", base),
+        }
+    }
+
+    /// 为请求附加 API Key / 管理员 JWT 认证头
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        let request = match self.api_key {
+            Some(ref key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        };
+        match self.bearer_token {
+            Some(ref token) => request.header(AUTHORIZATION_HEADER, format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    /// 发送请求并检查状态码，认证失败时上报
+    async fn send(&self, request: RequestBuilder, action: &str) -> Result<Response, AppError> {
+        let response = request.send().await.map_err(|e| {
+            log::error!("{} HTTP 请求失败：{}", action, e);
+            AppError::from(e)
+        })?;
+
+        let status = response.status();
+        log::info!("{} 收到 HTTP 响应，状态码：{}", action, status);
+
+        if !status.is_success() {
+            let retry_after = retry_after_secs(&response);
+            let body = response.text().await.unwrap_or_default();
+            log::error!("{}失败：{} - {}", action, status, body);
+            let mut error = AppError::from_status(status.as_u16(), body);
+            error.retry_after = retry_after;
+            if error.is_auth_error() && self.api_key.is_some() {
+                if let Some((ref profile_id, ref sender)) = self.auth_errors {
+                    let _ = sender.send((profile_id.clone(), error.clone()));
+                }
+            }
+            return Err(error);
+        }
+        Ok(response)
+    }
+
+    /// 发送请求、检查状态码并解析 JSON 响应
+    async fn execute<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        action: &str,
+    ) -> Result<T, AppError> {
+        let response = self.send(request, action).await?;
+        let text = response.text().await.map_err(|e| {
+            log::error!("{} 读取响应文本失败：{}", action, e);
+            AppError::from(e)
+        })?;
+
+        log::debug!("{} 后端返回的原始 JSON: {}", action, text);
+
+        serde_json::from_str(&text).map_err(|e| {
+            log::error!("{} 解析响应 JSON 失败：{}", action, e);
+            let mut error = AppError::bad_response(format!("解析响应失败：{}", e));
+            error.body = Some(text.clone());
+            error
+        })
+    }
+
+    /// POST /infer/single
+    pub async fn submit_single(
+        &self,
+        client_id: &str,
+        request: &SingleModeRequest,
+    ) -> Result<AsyncTaskResponse, AppError> {
+        let api_url = self.url("/infer/single");
+        log::info!("发送单模态推理请求到：{}", api_url);
+        log::info!("client_id: {}, 图片数量：{}", client_id, request.images.len());
+
+        let builder = self
+            .authorized(self.http.post(&api_url))
+            .header(CLIENT_ID_HEADER, client_id)
+            .json(request);
+        self.execute(builder, "单模态推理请求").await
+    }
+
+    /// POST /infer/fusion
+    pub async fn submit_fusion(
+        &self,
+        client_id: &str,
+        request: &FusionModeRequest,
+    ) -> Result<AsyncTaskResponse, AppError> {
+        let api_url = self.url("/infer/fusion");
+        log::info!("发送融合模式推理请求到：{}", api_url);
+        log::info!("client_id: {}, 图像对数量：{}", client_id, request.pairs.len());
+
+        let builder = self
+            .authorized(self.http.post(&api_url))
+            .header(CLIENT_ID_HEADER, client_id)
+            .json(request);
+        self.execute(builder, "融合模式推理请求").await
+    }
+
+    /// POST /infer/single，请求体由调用方流式生成（图片在 Rust 端读取和编码）
+    pub async fn submit_single_stream(
+        &self,
+        client_id: &str,
+        body: Body,
+        image_count: usize,
+    ) -> Result<AsyncTaskResponse, AppError> {
+        let api_url = self.url("/infer/single");
+        log::info!("发送单模态推理请求到：{}（流式请求体）", api_url);
+        log::info!("client_id: {}, 图片数量：{}", client_id, image_count);
+
+        let builder = self
+            .authorized(self.http.post(&api_url))
+            .header(CLIENT_ID_HEADER, client_id)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        self.execute(builder, "单模态推理请求").await
+    }
+
+    /// POST /infer/fusion，请求体由调用方流式生成
+    pub async fn submit_fusion_stream(
+        &self,
+        client_id: &str,
+        body: Body,
+        pair_count: usize,
+    ) -> Result<AsyncTaskResponse, AppError> {
+        let api_url = self.url("/infer/fusion");
+        log::info!("发送融合模式推理请求到：{}（流式请求体）", api_url);
+        log::info!("client_id: {}, 图像对数量：{}", client_id, pair_count);
+
+        let builder = self
+            .authorized(self.http.post(&api_url))
+            .header(CLIENT_ID_HEADER, client_id)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        self.execute(builder, "融合模式推理请求").await
+    }
+
+    /// DELETE /infer/task/{task_id}
+    pub async fn cancel_task(&self, task_id: &str) -> Result<AsyncTaskResponse, AppError> {
+        let api_url = self.url_with_id("/infer/task", task_id)?;
+        log::info!("发送取消任务请求，task_id: {}", task_id);
+
+        let builder = self.authorized(self.http.delete(&api_url));
+        self.execute(builder, "取消任务").await
+    }
+
+    /// GET /infer/task/{task_id}
+    pub async fn task_status(&self, task_id: &str) -> Result<BackendTaskStatusResponse, AppError> {
+        let api_url = self.url_with_id("/infer/task", task_id)?;
+        log::info!("查询任务状态，task_id: {}", task_id);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        self.execute(builder, "任务状态查询").await
+    }
+
+    /// GET /infer/tasks
+    pub async fn list_tasks(&self) -> Result<BackendTaskListResponse, AppError> {
+        let api_url = self.url("/infer/tasks");
+        log::info!("查询活跃任务列表：{}", api_url);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        self.execute(builder, "活跃任务列表查询").await
+    }
+
+    /// GET /health（不需要认证，使用独立的超时时间）
+    pub async fn health(&self, timeout: Duration) -> Result<BackendHealthResponse, AppError> {
+        let builder = self.http.get(self.url("/health")).timeout(timeout);
+        self.execute(builder, "健康检查").await
+    }
+
+    /// POST /auth/activate
+    pub async fn activate(&self, code: &str) -> Result<BackendActivateResponse, AppError> {
+        let api_url = self.url("/auth/activate");
+        log::info!("发送激活码验证请求到：{}", api_url);
+
+        let builder = self
+            .http
+            .post(&api_url)
+            .header("User-Agent", "frontend-tauri-app/0.1.0")
+            .json(&BackendActivateRequest {
+                code: code.to_string(),
+            });
+        self.execute(builder, "激活码验证").await
+    }
+
+    /// POST /auth/token（OAuth2 密码模式，表单提交）
+    pub async fn admin_token(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<BackendTokenResponse, AppError> {
+        let api_url = self.url("/auth/token");
+        log::info!("管理员登录：{}（用户：{}）", api_url, username);
+
+        let builder = self
+            .http
+            .post(&api_url)
+            .form(&[("username", username), ("password", password)]);
+        self.execute(builder, "管理员登录").await
+    }
+
+    /// POST /auth/activation-codes
+    pub async fn create_activation_codes(
+        &self,
+        params: &ActivationCodeCreateParams,
+    ) -> Result<BackendActivationCodeCreateResponse, AppError> {
+        let api_url = self.url("/auth/activation-codes");
+        log::info!("生成激活码：{} {:?}", api_url, params);
+
+        let builder = self.authorized(self.http.post(&api_url)).json(params);
+        self.execute(builder, "生成激活码").await
+    }
+
+    /// GET /auth/activation-codes
+    pub async fn list_activation_codes(
+        &self,
+        params: &ActivationCodeListParams,
+    ) -> Result<BackendActivationCodeListResponse, AppError> {
+        let mut query = Vec::new();
+        if let Some(ref status) = params.status {
+            query.push(("status", status.clone()));
+        }
+        if let Some(page) = params.page {
+            query.push(("page", page.to_string()));
+        }
+        if let Some(page_size) = params.page_size {
+            query.push(("page_size", page_size.to_string()));
+        }
+
+        let api_url = self.url("/auth/activation-codes");
+        log::info!("查询激活码列表：{} {:?}", api_url, query);
+
+        let builder = self.authorized(self.http.get(&api_url)).query(&query);
+        self.execute(builder, "激活码列表查询").await
+    }
+
+    /// PUT /auth/activation-codes/{code}
+    pub async fn update_activation_code(
+        &self,
+        code: &str,
+        params: &ActivationCodeUpdateParams,
+    ) -> Result<BackendActivationCode, AppError> {
+        let api_url = self.url_with_id("/auth/activation-codes", code)?;
+        log::info!("修改激活码：{} {:?}", api_url, params);
+
+        let builder = self.authorized(self.http.put(&api_url)).json(params);
+        self.execute(builder, "修改激活码").await
+    }
+
+    /// DELETE /auth/activation-codes/{code}
+    pub async fn revoke_activation_code(&self, code: &str) -> Result<serde_json::Value, AppError> {
+        let api_url = self.url_with_id("/auth/activation-codes", code)?;
+        log::info!("吊销激活码：{}", api_url);
+
+        let builder = self.authorized(self.http.delete(&api_url));
+        self.execute(builder, "吊销激活码").await
+    }
+
+    /// GET /infer/queue/status
+    pub async fn queue_status(&self) -> Result<BackendQueueStatusResponse, AppError> {
+        let api_url = self.url("/infer/queue/status");
+        log::info!("查询推理队列状态：{}", api_url);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        self.execute(builder, "队列状态查询").await
+    }
+
+    /// POST /storage/images
+    pub async fn upload_image(
+        &self,
+        request: &BackendImageUploadRequest,
+    ) -> Result<BackendStoredImage, AppError> {
+        let api_url = self.url("/storage/images");
+        log::info!(
+            "上传图片：{}（task_id: {:?}，image_index: {:?}）",
+            api_url,
+            request.task_id,
+            request.image_index
+        );
+
+        let builder = self.authorized(self.http.post(&api_url)).json(request);
+        self.execute(builder, "图片上传").await
+    }
+
+    /// GET /storage/images
+    pub async fn list_images(
+        &self,
+        params: &StoredImageListParams,
+    ) -> Result<BackendStoredImageListResponse, AppError> {
+        let mut query = Vec::new();
+        if let Some(ref task_id) = params.task_id {
+            query.push(("task_id", task_id.clone()));
+        }
+        if let Some(page) = params.page {
+            query.push(("page", page.to_string()));
+        }
+        if let Some(page_size) = params.page_size {
+            query.push(("page_size", page_size.to_string()));
+        }
+
+        let api_url = self.url("/storage/images");
+        log::info!("查询已存储图片：{} {:?}", api_url, query);
+
+        let builder = self.authorized(self.http.get(&api_url)).query(&query);
+        self.execute(builder, "图片列表查询").await
+    }
+
+    /// GET /storage/images/{image_id}，返回图片内容和 Content-Type
+    pub async fn fetch_image(&self, image_id: &str) -> Result<(Vec<u8>, Option<String>), AppError> {
+        let api_url = self.url_with_id("/storage/images", image_id)?;
+        log::info!("下载图片：{}", api_url);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        let response = self.send(builder, "图片下载").await?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let bytes = response.bytes().await.map_err(|e| {
+            log::error!("图片下载 读取响应失败：{}", e);
+            AppError::from(e)
+        })?;
+        Ok((bytes.to_vec(), content_type))
+    }
+
+    /// DELETE /storage/images/{image_id}
+    pub async fn delete_image(&self, image_id: &str) -> Result<serde_json::Value, AppError> {
+        let api_url = self.url_with_id("/storage/images", image_id)?;
+        log::info!("删除已存储图片：{}", api_url);
+
+        let builder = self.authorized(self.http.delete(&api_url));
+        self.execute(builder, "图片删除").await
+    }
+
+    /// GET /history
+    pub async fn query_history(
+        &self,
+        params: &HistoryQueryParams,
+    ) -> Result<BackendHistoryQueryResponse, AppError> {
+        let mut query = Vec::new();
+        if let Some(ref client_id) = params.client_id {
+            query.push(("client_id", client_id.clone()));
+        }
+        if let Some(ref mode) = params.mode {
+            query.push(("mode", mode.clone()));
+        }
+        if let Some(ref status) = params.status {
+            query.push(("status", status.clone()));
+        }
+        // 数字参数由前端以字符串传入，无法解析时忽略
+        push_number(&mut query, "days", params.days.as_deref());
+        push_number(&mut query, "page", params.page.as_deref());
+        push_number(&mut query, "page_size", params.page_size.as_deref());
+
+        let api_url = self.url("/history");
+        log::info!("查询历史记录：{} {:?}", api_url, query);
+
+        let builder = self.authorized(self.http.get(&api_url)).query(&query);
+        self.execute(builder, "历史查询").await
+    }
+
+    /// GET /history/stats
+    pub async fn history_stats(
+        &self,
+        params: Option<&HistoryStatsParams>,
+    ) -> Result<BackendHistoryStatsResponse, AppError> {
+        let mut query = Vec::new();
+        if let Some(p) = params {
+            if let Some(ref client_id) = p.client_id {
+                query.push(("client_id", client_id.clone()));
+            }
+            push_number(&mut query, "days", p.days.as_deref());
+        }
+
+        let api_url = self.url("/history/stats");
+        log::info!("获取历史统计：{} {:?}", api_url, query);
+
+        let builder = self.authorized(self.http.get(&api_url)).query(&query);
+        self.execute(builder, "统计查询").await
+    }
+
+    /// DELETE /history
+    pub async fn delete_history(
+        &self,
+        params: &HistoryDeleteParams,
+    ) -> Result<BackendHistoryDeleteResponse, AppError> {
+        let mut query = Vec::new();
+        if let Some(ref task_ids) = params.task_ids {
+            query.push(("task_ids", task_ids.join(",")));
+        }
+        if let Some(days_ago) = params.days_ago {
+            query.push(("days_ago", days_ago.to_string()));
+        }
+
+        let api_url = self.url("/history");
+        log::info!("删除历史记录：{} {:?}", api_url, query);
+
+        let builder = self.authorized(self.http.delete(&api_url)).query(&query);
+        self.execute(builder, "历史删除").await
+    }
+
+    /// GET /history/task/{task_id}
+    pub async fn history_task(&self, task_id: &str) -> Result<BackendHistoryTaskItem, AppError> {
+        let api_url = self.url_with_id("/history/task", task_id)?;
+        log::info!("获取历史任务详情：{}", api_url);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        self.execute(builder, "历史任务详情查询").await
+    }
+
+    /// GET /history/all
+    pub async fn all_history(&self) -> Result<BackendHistoryAllResponse, AppError> {
+        let api_url = self.url("/history/all");
+        log::info!("获取所有历史记录：{}", api_url);
+
+        let builder = self.authorized(self.http.get(&api_url));
+        self.execute(builder, "获取所有历史记录").await
+    }
+}
+
+/// 将字符串形式的数字参数加入查询列表
+fn push_number(query: &mut Vec<(&'static str, String)>, key: &'static str, value: Option<&str>) {
+    if let Some(n) = value.and_then(|v| v.parse::<u32>().ok()) {
+        query.push((key, n.to_string()));
+    }
+}
@@ -0,0 +1,1103 @@
+use crate::api::{ApiClient, AsyncTaskResponse, FusionModeRequest, SingleModeRequest};
+use crate::config::{BatchConfig, ConfigState, PreprocessConfig, RetryConfig};
+use crate::error::AppError;
+use crate::image_files::{self, FusionModePathRequest, SingleModePathRequest};
+use crate::profiles::ProfileState;
+use crate::retry::{self, RetryLink};
+use crate::tasks::{now_millis, TaskRegistry, TaskStatus};
+use crate::ws::{self, DetectionResultItem, WsConnectionStateRef, WsEventMessage};
+use reqwest::Body;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Notify;
+
+/// 没有收到子任务事件时，调度器重新检查子任务状态的间隔
+const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 保留的已结束批量任务数量上限
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// 批量任务 ID 前缀
+const JOB_ID_PREFIX: &str = "batch_";
+
+/// 持久化任务队列文件名（位于应用数据目录）
+const QUEUE_FILE: &str = "batch_jobs.json";
+
+/// 待提交的检测数据
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) enum BatchPayload {
+    Single(SingleModeRequest),
+    Fusion(FusionModeRequest),
+    SinglePaths(SingleModePathRequest, PreprocessConfig),
+    FusionPaths(FusionModePathRequest, PreprocessConfig),
+}
+
+impl BatchPayload {
+    /// 图片数量（融合模式为图片对数）
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            BatchPayload::Single(r) => r.images.len(),
+            BatchPayload::Fusion(r) => r.pairs.len(),
+            BatchPayload::SinglePaths(r, _) => r.image_paths.len(),
+            BatchPayload::FusionPaths(r, _) => r.pairs.len(),
+        }
+    }
+
+    fn mode(&self) -> &str {
+        match self {
+            BatchPayload::Single(r) => &r.mode,
+            BatchPayload::Fusion(r) => &r.mode,
+            BatchPayload::SinglePaths(r, _) => &r.mode,
+            BatchPayload::FusionPaths(r, _) => &r.mode,
+        }
+    }
+
+    /// 按路径提交的任务保存到磁盘，应用重启后继续提交；Base64 数据只保存在内存中
+    fn is_persistent(&self) -> bool {
+        matches!(self, BatchPayload::SinglePaths(..) | BatchPayload::FusionPaths(..))
+    }
+
+    fn modality(&self) -> Option<&str> {
+        match self {
+            BatchPayload::Single(r) => Some(&r.modality),
+            BatchPayload::SinglePaths(r, _) => Some(&r.modality),
+            BatchPayload::Fusion(_) | BatchPayload::FusionPaths(..) => None,
+        }
+    }
+
+    /// 取出 [start, end) 范围内的图片作为一个子任务
+    fn slice(&self, start: usize, end: usize) -> BatchPayload {
+        match self {
+            BatchPayload::Single(r) => BatchPayload::Single(SingleModeRequest {
+                mode: r.mode.clone(),
+                modality: r.modality.clone(),
+                images: r.images[start..end].to_vec(),
+            }),
+            BatchPayload::Fusion(r) => BatchPayload::Fusion(FusionModeRequest {
+                mode: r.mode.clone(),
+                pairs: r.pairs[start..end].to_vec(),
+            }),
+            BatchPayload::SinglePaths(r, config) => BatchPayload::SinglePaths(
+                SingleModePathRequest {
+                    mode: r.mode.clone(),
+                    modality: r.modality.clone(),
+                    image_paths: r.image_paths[start..end].to_vec(),
+                },
+                config.clone(),
+            ),
+            BatchPayload::FusionPaths(r, config) => BatchPayload::FusionPaths(
+                FusionModePathRequest {
+                    mode: r.mode.clone(),
+                    pairs: r.pairs[start..end].to_vec(),
+                },
+                config.clone(),
+            ),
+        }
+    }
+
+    /// 按索引取出部分图片（用于重新提交失败的图片）
+    pub(crate) fn select(&self, indices: &[usize]) -> BatchPayload {
+        fn pick<T: Clone>(items: &[T], indices: &[usize]) -> Vec<T> {
+            indices.iter().map(|&i| items[i].clone()).collect()
+        }
+        match self {
+            BatchPayload::Single(r) => BatchPayload::Single(SingleModeRequest {
+                mode: r.mode.clone(),
+                modality: r.modality.clone(),
+                images: pick(&r.images, indices),
+            }),
+            BatchPayload::Fusion(r) => BatchPayload::Fusion(FusionModeRequest {
+                mode: r.mode.clone(),
+                pairs: pick(&r.pairs, indices),
+            }),
+            BatchPayload::SinglePaths(r, config) => BatchPayload::SinglePaths(
+                SingleModePathRequest {
+                    mode: r.mode.clone(),
+                    modality: r.modality.clone(),
+                    image_paths: pick(&r.image_paths, indices),
+                },
+                config.clone(),
+            ),
+            BatchPayload::FusionPaths(r, config) => BatchPayload::FusionPaths(
+                FusionModePathRequest {
+                    mode: r.mode.clone(),
+                    pairs: pick(&r.pairs, indices),
+                },
+                config.clone(),
+            ),
+        }
+    }
+
+    async fn submit(self, client: &ApiClient, client_id: &str) -> Result<AsyncTaskResponse, AppError> {
+        match self {
+            BatchPayload::Single(request) => client.submit_single(client_id, &request).await,
+            BatchPayload::Fusion(request) => client.submit_fusion(client_id, &request).await,
+            BatchPayload::SinglePaths(request, config) => {
+                let count = request.image_paths.len();
+                let body = Body::wrap_stream(image_files::single_request_body(request, config));
+                client.submit_single_stream(client_id, body, count).await
+            }
+            BatchPayload::FusionPaths(request, config) => {
+                let count = request.pairs.len();
+                let body = Body::wrap_stream(image_files::fusion_request_body(request, config));
+                client.submit_fusion_stream(client_id, body, count).await
+            }
+        }
+    }
+
+    /// 提交任务，确定未被服务器处理的错误（见 [`retry::is_retryable`]）按 `retry` 配置退避后重试
+    async fn submit_with_retry(
+        &self,
+        client: &ApiClient,
+        client_id: &str,
+        config: &RetryConfig,
+    ) -> Result<AsyncTaskResponse, AppError> {
+        let mut attempt = 1;
+        loop {
+            match self.clone().submit(client, client_id).await {
+                Err(e) if attempt < config.max_attempts && retry::is_retryable(config, &e) => {
+                    let delay = retry::retry_delay(config, attempt, &e);
+                    log::warn!(
+                        "提交任务失败（第 {} 次）：{}，{} 毫秒后重试",
+                        attempt,
+                        e,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// 子任务（返回给前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchChunk {
+    /// 第一张图片在整批中的索引
+    pub offset: u32,
+    pub total_items: u32,
+    pub processed_items: u32,
+    /// 提交成功后的后端任务 ID
+    pub task_id: Option<String>,
+    /// Pending 表示尚未提交，Running 表示提交中或运行中
+    pub status: TaskStatus,
+    pub error: Option<String>,
+}
+
+/// 批量任务（返回给前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJobInfo {
+    pub job_id: String,
+    pub mode: String,
+    pub modality: Option<String>,
+    /// 提交时的服务器配置，切换到其他服务器时暂不提交
+    pub profile_id: String,
+    pub total_items: u32,
+    pub processed_items: u32,
+    pub status: TaskStatus,
+    /// 暂停后不再提交新的子任务（已提交的子任务继续运行）
+    pub paused: bool,
+    /// 是否保存到磁盘（按路径提交的任务）
+    pub persistent: bool,
+    pub chunks: Vec<BatchChunk>,
+    pub submitted_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchJob {
+    info: BatchJobInfo,
+    /// 批量任务结束后释放
+    payload: Option<BatchPayload>,
+    /// 已收到的单张结果（image_index 已映射为整批中的索引）
+    results: Vec<DetectionResultItem>,
+    cancelled: bool,
+    /// 重试失败图片的后续任务与原任务的关联
+    #[serde(default)]
+    retry: Option<RetryLink>,
+    #[serde(skip)]
+    notify: Arc<Notify>,
+}
+
+impl BatchJob {
+    fn add_result(&mut self, result: DetectionResultItem) {
+        let duplicate = result.image_index.is_some()
+            && self.results.iter().any(|r| r.image_index == result.image_index);
+        if !duplicate {
+            self.results.push(result);
+        }
+    }
+
+    fn update_processed(&mut self) {
+        self.info.processed_items = self.info.chunks.iter().map(|c| c.processed_items).sum();
+    }
+
+    /// 全部子任务结束时汇总状态并发送整批的结束事件，返回是否在本次结束
+    fn finish_if_done(&mut self, app: &AppHandle) -> bool {
+        if self.info.finished_at.is_some() || self.info.chunks.iter().any(|c| !c.status.is_terminal()) {
+            return false;
+        }
+
+        let chunk_count = self.info.chunks.len();
+        let failed = self
+            .info
+            .chunks
+            .iter()
+            .filter(|c| c.status == TaskStatus::Failed)
+            .count();
+        let partial = self
+            .info
+            .chunks
+            .iter()
+            .any(|c| c.status == TaskStatus::PartialFailure);
+        let (status, message) = if self.cancelled {
+            (TaskStatus::Cancelled, "批量任务已取消".to_string())
+        } else if failed == chunk_count {
+            let error = self.info.chunks.iter().find_map(|c| c.error.clone());
+            (TaskStatus::Failed, error.unwrap_or_else(|| "全部子任务失败".to_string()))
+        } else if failed > 0 || partial {
+            (
+                TaskStatus::PartialFailure,
+                format!("{} 个子任务中 {} 个失败", chunk_count, failed),
+            )
+        } else {
+            (TaskStatus::Completed, "批量任务已完成".to_string())
+        };
+
+        self.info.status = status;
+        self.info.finished_at = Some(now_millis());
+        let payload = self.payload.take();
+        self.results.sort_by_key(|r| r.image_index);
+        log::info!(
+            "批量任务 {} 结束：{}，已处理 {}/{}",
+            self.info.job_id,
+            status.as_str(),
+            self.info.processed_items,
+            self.info.total_items
+        );
+
+        let (event_name, event_type) = match status {
+            TaskStatus::Failed => ("ws_task_failed", "task_failed"),
+            _ => ("ws_task_completed", "task_completed"),
+        };
+        let _ = app.emit(
+            event_name,
+            &WsEventMessage {
+                event_type: event_type.to_string(),
+                task_id: self.info.job_id.clone(),
+                status: Some(status.as_str().to_string()),
+                message: Some(message),
+                result: None,
+                total_items: Some(self.info.total_items),
+                processed_items: Some(self.info.processed_items),
+                completed_results: Some(self.results.clone()),
+            },
+        );
+
+        // 只有按路径提交的任务重试失败的图片（Base64 数据在全部子任务提交后已释放）
+        if let (Some(payload), TaskStatus::Completed | TaskStatus::PartialFailure) = (payload, status) {
+            if payload.is_persistent() {
+                retry::retry_failed_images(app, &self.info.job_id, payload, self.retry.clone(), &self.results);
+            }
+        }
+        true
+    }
+}
+
+/// 任务队列文件的写入状态
+///
+/// 快照在持有 [`BatchJobs`] 锁时生成并编号，在阻塞线程中写入；
+/// 写入时持有 `written` 锁并跳过比已写入快照更旧的快照，保证文件内容不会回退。
+#[derive(Default)]
+struct QueueWriter {
+    /// 最近一次生成的快照序号
+    next_seq: AtomicU64,
+    /// 已写入磁盘的快照序号
+    written: Arc<Mutex<u64>>,
+}
+
+#[derive(Default)]
+struct BatchInner {
+    jobs: HashMap<String, BatchJob>,
+    /// 子任务 ID → (批量任务 ID, 子任务序号)
+    tasks: HashMap<String, (String, usize)>,
+    /// 正在提交的子任务数量
+    submitting: usize,
+    /// 提交响应返回前收到的未知任务推送（可能属于正在提交的任务）
+    buffered: Vec<(String, WsEventMessage)>,
+    queue: QueueWriter,
+}
+
+impl BatchInner {
+    /// 把子任务事件合并到批量任务：映射 image_index、汇总进度，全部子任务结束后发送整批的结束事件
+    fn merge_event(&mut self, app: &AppHandle, event_name: &str, event: WsEventMessage) {
+        let Some((job_id, index)) = self.tasks.get(&event.task_id).cloned() else {
+            return;
+        };
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return;
+        };
+        if job.info.finished_at.is_some() {
+            return;
+        }
+
+        let chunk = &mut job.info.chunks[index];
+        let offset = chunk.offset;
+        let remap = |mut result: DetectionResultItem| {
+            result.image_index = result.image_index.map(|i| i + offset);
+            result
+        };
+
+        if event_name == "ws_progress" {
+            if let Some(processed) = event.processed_items {
+                chunk.processed_items = chunk.processed_items.max(processed.min(chunk.total_items));
+            }
+            let result = event.result.map(remap);
+            if let Some(ref result) = result {
+                job.add_result(result.clone());
+            }
+            job.update_processed();
+            let _ = app.emit(
+                "ws_progress",
+                &WsEventMessage {
+                    event_type: "progress".to_string(),
+                    task_id: job_id,
+                    status: Some("running".to_string()),
+                    message: event.message,
+                    result,
+                    total_items: Some(job.info.total_items),
+                    processed_items: Some(job.info.processed_items),
+                    completed_results: None,
+                },
+            );
+            return;
+        }
+
+        chunk.status = if event_name == "ws_task_failed" {
+            TaskStatus::Failed
+        } else {
+            TaskStatus::from_backend(event.status.as_deref().unwrap_or("completed"))
+        };
+        if chunk.status == TaskStatus::Failed {
+            chunk.error = event.message.clone();
+        } else {
+            let processed = event.processed_items.unwrap_or(chunk.total_items);
+            chunk.processed_items = chunk.processed_items.max(processed.min(chunk.total_items));
+        }
+        log::info!(
+            "批量任务 {} 的第 {} 个子任务结束：{}",
+            job_id,
+            index + 1,
+            chunk.status.as_str()
+        );
+        for result in event.completed_results.unwrap_or_default() {
+            job.add_result(remap(result));
+        }
+        job.update_processed();
+        job.notify.notify_one();
+        job.finish_if_done(app);
+        self.job_changed(app, &job_id);
+    }
+
+    /// 推送是否需要暂存：有任务正在提交，且该任务既不是已知的子任务，也未在任务注册表中登记
+    fn should_buffer(&self, app: &AppHandle, task_id: &str) -> bool {
+        self.submitting > 0
+            && !self.tasks.contains_key(task_id)
+            && !app.state::<TaskRegistry>().is_registered(task_id)
+    }
+
+    /// 处理提交期间暂存的推送
+    fn flush_buffered(&mut self, app: &AppHandle) {
+        for (event_name, event) in std::mem::take(&mut self.buffered) {
+            if self.tasks.contains_key(&event.task_id) {
+                self.merge_event(app, &event_name, event);
+            } else if self.should_buffer(app, &event.task_id) {
+                self.buffered.push((event_name, event));
+            } else {
+                let _ = app.emit(&event_name, &event);
+            }
+        }
+    }
+
+    /// 批量任务状态变化：发送 batch_job_updated 事件，并更新磁盘上的任务队列
+    fn job_changed(&self, app: &AppHandle, job_id: &str) {
+        let Some(job) = self.jobs.get(job_id) else {
+            return;
+        };
+        let _ = app.emit("batch_job_updated", &job.info);
+        if job.info.persistent {
+            self.persist();
+        }
+    }
+
+    /// 把未结束的持久化任务写入磁盘
+    ///
+    /// 在锁内只生成快照，文件写入交给阻塞线程，不阻塞持有锁的异步任务和事件处理。
+    fn persist(&self) {
+        let jobs: Vec<&BatchJob> = self
+            .jobs
+            .values()
+            .filter(|job| job.info.persistent && job.info.finished_at.is_none())
+            .collect();
+        let snapshot = queue_file().and_then(|path| {
+            serde_json::to_vec(&jobs)
+                .map(|content| (path, content))
+                .map_err(|e| AppError::config(format!("序列化任务队列失败：{}", e)))
+        });
+        let (path, content) = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::error!("保存批量任务队列失败：{}", e);
+                return;
+            }
+        };
+
+        let seq = self.queue.next_seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let written = self.queue.written.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut written = written.lock().unwrap();
+            if *written >= seq {
+                return;
+            }
+            match write_queue(&path, &content) {
+                Ok(()) => *written = seq,
+                Err(e) => log::error!("保存批量任务队列失败：{}", e),
+            }
+        });
+    }
+
+    /// 已结束的批量任务超出上限时淘汰最早结束的
+    fn evict_finished(&mut self) {
+        let mut finished: Vec<(u64, String)> = self
+            .jobs
+            .values()
+            .filter_map(|job| job.info.finished_at.map(|t| (t, job.info.job_id.clone())))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        for (_, job_id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+            self.jobs.remove(job_id);
+            self.tasks.retain(|_, (id, _)| id != job_id);
+        }
+    }
+}
+
+/// 批量任务状态（用于 Tauri State）
+///
+/// 图片数量超过 `batch.max_images_per_task` 时拆分为多个子任务，对前端表现为一个任务：
+/// 子任务的推送经 [`emit_task_event`] 合并为以批量任务 ID 发送的事件。
+#[derive(Default)]
+pub struct BatchJobs(Mutex<BatchInner>);
+
+/// 发送任务事件（ws_progress / ws_task_completed / ws_task_failed）
+///
+/// 属于批量任务的子任务事件会被合并后以批量任务 ID 发送；任务提交期间收到的
+/// 未知任务事件先暂存，待提交响应返回后再判断归属。已登记的普通任务不受影响。
+pub(crate) fn emit_task_event(app: &AppHandle, event_name: &str, event: WsEventMessage) {
+    let batch = app.state::<BatchJobs>();
+    let mut inner = batch.0.lock().unwrap();
+    if inner.tasks.contains_key(&event.task_id) {
+        inner.merge_event(app, event_name, event);
+    } else if inner.should_buffer(app, &event.task_id) {
+        inner.buffered.push((event_name.to_string(), event));
+    } else {
+        drop(inner);
+        let _ = app.emit(event_name, &event);
+    }
+}
+
+/// 提交检测任务
+///
+/// Base64 数据且图片数量不超过 `batch.max_images_per_task` 时直接提交；否则作为批量任务
+/// 拆分为多个子任务（按路径提交的任务和重试任务无论数量都作为批量任务，以便保存到磁盘、
+/// 重试失败的图片），
+/// 同步提交第一个（尽早暴露授权等错误），其余由后台调度器按 `batch.max_concurrent_tasks`
+/// 限制并发提交，返回批量任务 ID。
+pub(crate) async fn submit(
+    app: &AppHandle,
+    client: &ApiClient,
+    client_id: &str,
+    payload: BatchPayload,
+    config: &BatchConfig,
+) -> Result<AsyncTaskResponse, AppError> {
+    submit_linked(app, client, client_id, payload, config, None).await
+}
+
+/// Base64 提交的任务不保留图片数据，结果为 error 的图片不会重试；开启图片重试时在响应中说明
+const BASE64_NO_RETRY_NOTICE: &str = "Base64 提交的任务不重试检测失败的图片，如需重试请按路径提交";
+
+/// 提交检测任务，`retry` 为重试失败图片的后续任务与原任务的关联
+pub(crate) async fn submit_linked(
+    app: &AppHandle,
+    client: &ApiClient,
+    client_id: &str,
+    payload: BatchPayload,
+    config: &BatchConfig,
+    retry: Option<RetryLink>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let skips_image_retry = !payload.is_persistent()
+        && retry.is_none()
+        && app.state::<ConfigState>().0.retry.image_max_attempts > 1;
+    let result = submit_payload(app, client, client_id, payload, config, retry).await;
+    match result {
+        Ok(mut response) if skips_image_retry => {
+            log::warn!("任务 {}：{}", response.task_id, BASE64_NO_RETRY_NOTICE);
+            response.message = format!("{}（{}）", response.message, BASE64_NO_RETRY_NOTICE);
+            Ok(response)
+        }
+        result => result,
+    }
+}
+
+/// 直接提交或拆分为批量任务提交
+async fn submit_payload(
+    app: &AppHandle,
+    client: &ApiClient,
+    client_id: &str,
+    payload: BatchPayload,
+    config: &BatchConfig,
+    retry: Option<RetryLink>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let total = payload.len();
+    let chunk_size = config.max_images_per_task.max(1);
+
+    if total <= chunk_size && !payload.is_persistent() && retry.is_none() {
+        let retry_config = app.state::<ConfigState>().0.retry.clone();
+        let result = payload.submit_with_retry(client, client_id, &retry_config).await;
+        if let Ok(ref task_response) = result {
+            app.state::<TaskRegistry>().register(
+                &task_response.task_id,
+                &app.state::<ProfileState>().active_profile().id,
+                payload.mode(),
+                payload.modality(),
+                total as u32,
+            );
+            log::info!("任务创建成功：task_id={}", task_response.task_id);
+        }
+        return result;
+    }
+
+    let job_id = format!("{}{}_{:06}", JOB_ID_PREFIX, now_millis(), ws::random_below(1_000_000));
+    let chunks: Vec<BatchChunk> = (0..total)
+        .step_by(chunk_size)
+        .map(|offset| BatchChunk {
+            offset: offset as u32,
+            total_items: (total - offset).min(chunk_size) as u32,
+            processed_items: 0,
+            task_id: None,
+            status: TaskStatus::Pending,
+            error: None,
+        })
+        .collect();
+    let chunk_count = chunks.len();
+    if let Some(ref link) = retry {
+        retry::link_submitted(app, &job_id, link);
+    }
+    let job = BatchJob {
+        info: BatchJobInfo {
+            job_id: job_id.clone(),
+            mode: payload.mode().to_string(),
+            modality: payload.modality().map(str::to_string),
+            profile_id: app.state::<ProfileState>().active_profile().id,
+            total_items: total as u32,
+            processed_items: 0,
+            status: TaskStatus::Running,
+            paused: false,
+            persistent: payload.is_persistent(),
+            chunks,
+            submitted_at: now_millis(),
+            finished_at: None,
+        },
+        payload: Some(payload),
+        results: Vec::new(),
+        cancelled: false,
+        retry,
+        notify: Arc::new(Notify::new()),
+    };
+    {
+        let batch = app.state::<BatchJobs>();
+        let mut inner = batch.0.lock().unwrap();
+        inner.jobs.insert(job_id.clone(), job);
+        inner.evict_finished();
+        inner.job_changed(app, &job_id);
+    }
+    log::info!(
+        "批量任务 {}：{} 张图片拆分为 {} 个子任务，最多同时运行 {} 个",
+        job_id,
+        total,
+        chunk_count,
+        config.max_concurrent_tasks.max(1)
+    );
+
+    if let Err(e) = submit_chunk(app, &job_id, 0, client, client_id, true).await {
+        let batch = app.state::<BatchJobs>();
+        let mut inner = batch.0.lock().unwrap();
+        inner.jobs.remove(&job_id);
+        inner.persist();
+        return Err(e);
+    }
+
+    tauri::async_runtime::spawn(run_scheduler(
+        app.clone(),
+        job_id.clone(),
+        client_id.to_string(),
+        config.max_concurrent_tasks.max(1),
+    ));
+
+    Ok(AsyncTaskResponse {
+        task_id: job_id,
+        message: format!("已拆分为 {} 个子任务", chunk_count),
+    })
+}
+
+/// 提交一个子任务
+///
+/// `synchronous` 为命令中同步提交的第一个子任务：失败时调用方尚未拿到批量任务 ID，
+/// 批量任务会被直接移除，不发送结束事件，只通过返回的错误通知调用方。
+async fn submit_chunk(
+    app: &AppHandle,
+    job_id: &str,
+    index: usize,
+    client: &ApiClient,
+    client_id: &str,
+    synchronous: bool,
+) -> Result<(), AppError> {
+    let batch = app.state::<BatchJobs>();
+    let (payload, mode, modality, profile_id, retry_link) = {
+        let mut inner = batch.0.lock().unwrap();
+        let job = inner
+            .jobs
+            .get_mut(job_id)
+            .ok_or_else(|| AppError::not_found(format!("批量任务不存在：{}", job_id)))?;
+        let chunk = &mut job.info.chunks[index];
+        chunk.status = TaskStatus::Running;
+        let start = chunk.offset as usize;
+        let end = start + chunk.total_items as usize;
+        let payload = job
+            .payload
+            .as_ref()
+            .map(|payload| payload.slice(start, end))
+            .ok_or_else(|| AppError::not_found(format!("批量任务已结束：{}", job_id)))?;
+        let mode = job.info.mode.clone();
+        let modality = job.info.modality.clone();
+        let profile_id = job.info.profile_id.clone();
+        let retry_link = job.retry.clone();
+        inner.submitting += 1;
+        (payload, mode, modality, profile_id, retry_link)
+    };
+
+    let items = payload.len() as u32;
+    let retry_config = app.state::<ConfigState>().0.retry.clone();
+    let result = payload.submit_with_retry(client, client_id, &retry_config).await;
+
+    let registry = app.state::<TaskRegistry>();
+    let mut cancel_task_id = None;
+    let outcome = {
+        let mut inner = batch.0.lock().unwrap();
+        inner.submitting -= 1;
+        let outcome = match result {
+            Ok(task_response) => {
+                registry.register(&task_response.task_id, &profile_id, &mode, modality.as_deref(), items);
+                if let Some(ref link) = retry_link {
+                    registry.link_retry(&task_response.task_id, &link.original_task_id, link.attempt);
+                }
+                log::info!(
+                    "批量任务 {} 的第 {} 个子任务创建成功：task_id={}",
+                    job_id,
+                    index + 1,
+                    task_response.task_id
+                );
+                inner
+                    .tasks
+                    .insert(task_response.task_id.clone(), (job_id.to_string(), index));
+                if let Some(job) = inner.jobs.get_mut(job_id) {
+                    job.info.chunks[index].task_id = Some(task_response.task_id.clone());
+                    // Base64 数据不用于重试，全部子任务提交后即可释放
+                    if !job.info.persistent && job.info.chunks.iter().all(|c| c.status != TaskStatus::Pending) {
+                        job.payload = None;
+                    }
+                    if job.cancelled {
+                        cancel_task_id = Some(task_response.task_id);
+                    }
+                }
+                Ok(())
+            }
+            Err(e) => {
+                log::error!("批量任务 {} 的第 {} 个子任务提交失败：{}", job_id, index + 1, e);
+                if let Some(job) = inner.jobs.get_mut(job_id).filter(|_| !synchronous) {
+                    let chunk = &mut job.info.chunks[index];
+                    chunk.status = TaskStatus::Failed;
+                    chunk.error = Some(e.message.clone());
+                    job.finish_if_done(app);
+                }
+                Err(e)
+            }
+        };
+        if !(synchronous && outcome.is_err()) {
+            inner.job_changed(app, job_id);
+        }
+        inner.flush_buffered(app);
+        outcome
+    };
+
+    // 提交期间批量任务已被取消
+    if let Some(task_id) = cancel_task_id {
+        if let Err(e) = client.cancel_task(&task_id).await {
+            log::warn!("取消子任务 {} 失败：{}", task_id, e);
+        }
+    }
+    outcome
+}
+
+/// 当前的 WebSocket client_id（断线重连后会变化）
+pub(crate) async fn current_client_id(app: &AppHandle) -> Option<String> {
+    app.state::<WsConnectionStateRef>().lock().await.client_id.clone()
+}
+
+/// 批量任务调度：保持最多 max_concurrent 个子任务运行，直到全部子任务提交并结束
+///
+/// 暂停、当前服务器配置与提交时不同、或没有可用的 client_id 时不提交新的子任务。
+async fn run_scheduler(app: AppHandle, job_id: String, client_id: String, max_concurrent: usize) {
+    loop {
+        let active_profile_id = app.state::<ProfileState>().active_profile().id;
+        let client_id = current_client_id(&app).await.unwrap_or_else(|| client_id.clone());
+        let (notify, next) = {
+            let batch = app.state::<BatchJobs>();
+            let registry = app.state::<TaskRegistry>();
+            let mut inner = batch.0.lock().unwrap();
+            let Some(job) = inner.jobs.get_mut(&job_id) else {
+                return;
+            };
+            if job.info.finished_at.is_some() {
+                return;
+            }
+
+            // 子任务的结束事件未经过合并时（例如记录已被淘汰），以任务注册表为准
+            let mut changed = false;
+            for chunk in job.info.chunks.iter_mut() {
+                if chunk.status != TaskStatus::Running {
+                    continue;
+                }
+                let finished = chunk
+                    .task_id
+                    .as_deref()
+                    .and_then(|task_id| registry.get(task_id))
+                    .filter(|record| record.status.is_terminal());
+                if let Some(record) = finished {
+                    chunk.status = record.status;
+                    chunk.processed_items = record.processed_items.min(chunk.total_items);
+                    changed = true;
+                }
+            }
+            job.update_processed();
+            if job.finish_if_done(&app) {
+                inner.job_changed(&app, &job_id);
+                return;
+            }
+
+            let running = job
+                .info
+                .chunks
+                .iter()
+                .filter(|c| c.status == TaskStatus::Running)
+                .count();
+            let blocked = job.cancelled
+                || job.info.paused
+                || job.info.profile_id != active_profile_id
+                || client_id.is_empty();
+            let next = if blocked || running >= max_concurrent {
+                None
+            } else {
+                job.info.chunks.iter().position(|c| c.status == TaskStatus::Pending)
+            };
+            let notify = job.notify.clone();
+            if changed {
+                inner.job_changed(&app, &job_id);
+            }
+            (notify, next)
+        };
+
+        let Some(index) = next else {
+            let _ = tokio::time::timeout(RECHECK_INTERVAL, notify.notified()).await;
+            continue;
+        };
+
+        let client = app.state::<ProfileState>().authorized_client();
+        match client {
+            Ok(client) => {
+                let _ = submit_chunk(&app, &job_id, index, &client, &client_id, false).await;
+            }
+            Err(e) => {
+                let batch = app.state::<BatchJobs>();
+                let mut inner = batch.0.lock().unwrap();
+                if let Some(job) = inner.jobs.get_mut(&job_id) {
+                    let chunk = &mut job.info.chunks[index];
+                    chunk.status = TaskStatus::Failed;
+                    chunk.error = Some(e.message);
+                }
+                inner.job_changed(&app, &job_id);
+            }
+        }
+    }
+}
+
+/// 取消批量任务：未提交的子任务不再提交，运行中的子任务逐个取消
+///
+/// 不是批量任务 ID 时返回 None。
+pub(crate) async fn cancel(
+    app: &AppHandle,
+    client: &ApiClient,
+    job_id: &str,
+) -> Option<Result<AsyncTaskResponse, AppError>> {
+    if !job_id.starts_with(JOB_ID_PREFIX) {
+        return None;
+    }
+
+    let running_tasks: Vec<String> = {
+        let batch = app.state::<BatchJobs>();
+        let mut inner = batch.0.lock().unwrap();
+        let job = inner.jobs.get_mut(job_id)?;
+        if job.info.finished_at.is_some() {
+            return Some(Err(AppError::validation("批量任务已结束")));
+        }
+        job.cancelled = true;
+        for chunk in job.info.chunks.iter_mut() {
+            if chunk.status == TaskStatus::Pending {
+                chunk.status = TaskStatus::Cancelled;
+            }
+        }
+        job.notify.notify_one();
+        job.finish_if_done(app);
+        let running_tasks = job
+            .info
+            .chunks
+            .iter()
+            .filter(|c| c.status == TaskStatus::Running)
+            .filter_map(|c| c.task_id.clone())
+            .collect();
+        inner.job_changed(app, job_id);
+        running_tasks
+    };
+
+    log::info!("取消批量任务 {}，运行中的子任务：{}", job_id, running_tasks.len());
+    for task_id in &running_tasks {
+        if let Err(e) = client.cancel_task(task_id).await {
+            log::warn!("取消子任务 {} 失败：{}", task_id, e);
+        }
+    }
+
+    Some(Ok(AsyncTaskResponse {
+        task_id: job_id.to_string(),
+        message: "已取消".to_string(),
+    }))
+}
+
+/// 暂停或继续批量任务
+fn set_paused(app: &AppHandle, job_id: &str, paused: bool) -> Result<BatchJobInfo, AppError> {
+    let batch = app.state::<BatchJobs>();
+    let mut inner = batch.0.lock().unwrap();
+    let job = inner
+        .jobs
+        .get_mut(job_id)
+        .ok_or_else(|| AppError::not_found(format!("批量任务不存在：{}", job_id)))?;
+    if job.info.finished_at.is_some() || job.cancelled {
+        return Err(AppError::validation("批量任务已结束"));
+    }
+    job.info.paused = paused;
+    job.notify.notify_one();
+    let info = job.info.clone();
+    inner.job_changed(app, job_id);
+    log::info!("批量任务 {} 已{}", job_id, if paused { "暂停" } else { "继续" });
+    Ok(info)
+}
+
+// ===== 持久化 =====
+
+/// 获取任务队列文件路径（与 profiles.json、shortcuts.json 一样保存在 config 目录）
+fn queue_file() -> Result<PathBuf, AppError> {
+    let project_path = std::env::var("PROJECT_PATH").map_err(|_| {
+        AppError::config("无法获取 PROJECT_PATH 环境变量，请检查.env 文件是否存在并正确配置")
+    })?;
+
+    let queue_path = PathBuf::from(project_path)
+        .join("src-tauri")
+        .join("config")
+        .join(QUEUE_FILE);
+
+    Ok(queue_path)
+}
+
+/// 写入任务队列文件（先写临时文件再替换，避免写入中断损坏队列）
+fn write_queue(path: &Path, content: &[u8]) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::config(format!("创建配置目录失败：{}", e)))?;
+    }
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content)
+        .and_then(|_| std::fs::rename(&temp, path))
+        .map_err(|e| AppError::config(format!("写入任务队列失败：{}", e)))
+}
+
+fn load_queue() -> Result<Vec<BatchJob>, AppError> {
+    let path = queue_file()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read(&path).map_err(|e| AppError::config(format!("读取任务队列失败：{}", e)))?;
+    serde_json::from_slice(&content).map_err(|e| AppError::config(format!("任务队列文件格式错误：{}", e)))
+}
+
+/// 启动时恢复上次未完成的批量任务
+///
+/// 已提交的子任务重新登记到任务注册表并轮询其状态（旧连接上的推送已丢失）；
+/// 提交中断的子任务重新排队。恢复的任务等待 WebSocket 连接建立后继续提交。
+pub(crate) fn resume_persisted_jobs(app: &AppHandle) {
+    let jobs = match load_queue() {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            log::error!("加载批量任务队列失败：{}", e);
+            return;
+        }
+    };
+    if jobs.is_empty() {
+        return;
+    }
+
+    let config = app.state::<ConfigState>().0.clone();
+    let registry = app.state::<TaskRegistry>();
+    let batch = app.state::<BatchJobs>();
+    let active_profile_id = app.state::<ProfileState>().active_profile().id;
+    let mut running_tasks = Vec::new();
+    let mut job_ids = Vec::new();
+    {
+        let mut inner = batch.0.lock().unwrap();
+        for mut job in jobs {
+            if job.payload.is_none() {
+                continue;
+            }
+            let job_id = job.info.job_id.clone();
+            for (index, chunk) in job.info.chunks.iter_mut().enumerate() {
+                if chunk.status != TaskStatus::Running {
+                    continue;
+                }
+                match chunk.task_id.clone() {
+                    Some(task_id) => {
+                        registry.register(
+                            &task_id,
+                            &job.info.profile_id,
+                            &job.info.mode,
+                            job.info.modality.as_deref(),
+                            chunk.total_items,
+                        );
+                        inner.tasks.insert(task_id.clone(), (job_id.clone(), index));
+                        // 其他服务器的子任务在切换回该服务器时恢复
+                        if job.info.profile_id == active_profile_id {
+                            running_tasks.push(task_id);
+                        }
+                    }
+                    None => chunk.status = TaskStatus::Pending,
+                }
+            }
+            job.notify = Arc::new(Notify::new());
+            log::info!(
+                "恢复批量任务 {}：已处理 {}/{}{}",
+                job_id,
+                job.info.processed_items,
+                job.info.total_items,
+                if job.info.paused { "（已暂停）" } else { "" }
+            );
+            inner.jobs.insert(job_id.clone(), job);
+            job_ids.push(job_id);
+        }
+        for job_id in &job_ids {
+            inner.job_changed(app, job_id);
+        }
+    }
+
+    let max_concurrent = config.batch.max_concurrent_tasks.max(1);
+    for job_id in job_ids {
+        tauri::async_runtime::spawn(run_scheduler(app.clone(), job_id, String::new(), max_concurrent));
+    }
+
+    if running_tasks.is_empty() {
+        return;
+    }
+    match app.state::<ProfileState>().authorized_client() {
+        Ok(client) => {
+            tauri::async_runtime::spawn(ws::recover_in_flight_tasks(
+                app.clone(),
+                client,
+                running_tasks,
+                Duration::from_millis(config.websocket.task_poll_interval_ms.max(100)),
+            ));
+        }
+        Err(e) => log::warn!("无法恢复已提交的子任务状态：{}", e),
+    }
+}
+
+// ===== 命令 =====
+
+/// 获取批量任务及其子任务的状态
+#[tauri::command]
+pub fn get_batch_job(job_id: String, batch: State<'_, BatchJobs>) -> Result<BatchJobInfo, AppError> {
+    batch
+        .0
+        .lock()
+        .unwrap()
+        .jobs
+        .get(&job_id)
+        .map(|job| job.info.clone())
+        .ok_or_else(|| AppError::not_found(format!("批量任务不存在：{}", job_id)))
+}
+
+/// 列出批量任务（包括启动时恢复的任务）
+#[tauri::command]
+pub fn list_batch_jobs(batch: State<'_, BatchJobs>) -> Result<Vec<BatchJobInfo>, AppError> {
+    let mut jobs: Vec<BatchJobInfo> = batch
+        .0
+        .lock()
+        .unwrap()
+        .jobs
+        .values()
+        .map(|job| job.info.clone())
+        .collect();
+    jobs.sort_by_key(|job| job.submitted_at);
+    Ok(jobs)
+}
+
+/// 暂停批量任务：不再提交新的子任务，已提交的子任务继续运行
+#[tauri::command]
+pub fn pause_batch_job(app: AppHandle, job_id: String) -> Result<BatchJobInfo, AppError> {
+    set_paused(&app, &job_id, true)
+}
+
+/// 继续已暂停的批量任务
+#[tauri::command]
+pub fn resume_batch_job(app: AppHandle, job_id: String) -> Result<BatchJobInfo, AppError> {
+    set_paused(&app, &job_id, false)
+}
+
+/// 取消批量任务
+#[tauri::command]
+pub async fn cancel_batch_job(
+    app: AppHandle,
+    job_id: String,
+    profiles: State<'_, ProfileState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    cancel(&app, &client, &job_id)
+        .await
+        .unwrap_or_else(|| Err(AppError::not_found(format!("批量任务不存在：{}", job_id))))
+}
@@ -1,3 +1,4 @@
+use crate::error::{AppError, ErrorCode};
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -7,6 +8,22 @@ use std::sync::Arc;
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AppConfig {
     pub image: ImageConfig,
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+    #[serde(default)]
+    pub transport: TransportConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub license: LicenseConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub pairing: PairingConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
 }
 
 /// 图片配置
@@ -14,6 +31,281 @@ pub struct AppConfig {
 pub struct ImageConfig {
     pub supported_formats: Vec<String>,
     pub max_file_size_mb: u64,
+    /// 最小分辨率（像素）
+    #[serde(default = "default_min_dimension")]
+    pub min_width: u32,
+    #[serde(default = "default_min_dimension")]
+    pub min_height: u32,
+    /// 最大分辨率（像素）
+    #[serde(default = "default_max_dimension")]
+    pub max_width: u32,
+    #[serde(default = "default_max_dimension")]
+    pub max_height: u32,
+    /// 提交前的预处理
+    #[serde(default)]
+    pub preprocess: PreprocessConfig,
+}
+
+fn default_min_dimension() -> u32 {
+    64
+}
+
+fn default_max_dimension() -> u32 {
+    8192
+}
+
+/// 预处理输出格式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PreprocessFormat {
+    /// 保持原格式
+    #[default]
+    Keep,
+    Jpeg,
+    Png,
+    /// WebP（无损编码）
+    Webp,
+}
+
+/// 图片预处理配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct PreprocessConfig {
+    pub enabled: bool,
+    /// 按 EXIF 方向信息旋转/翻转图片
+    pub apply_exif_orientation: bool,
+    /// 最长边上限（像素），超过时等比缩小，0 表示不缩放
+    pub max_edge: u32,
+    pub output_format: PreprocessFormat,
+    /// JPEG 编码质量（1-100）
+    pub jpeg_quality: u8,
+    /// 去除 EXIF、ICC 等元数据
+    pub strip_metadata: bool,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        PreprocessConfig {
+            enabled: false,
+            apply_exif_orientation: true,
+            max_edge: 2048,
+            output_format: PreprocessFormat::Keep,
+            jpeg_quality: 90,
+            strip_metadata: true,
+        }
+    }
+}
+
+/// WebSocket 配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct WebSocketConfig {
+    /// 心跳间隔（秒）
+    pub heartbeat_interval_secs: u64,
+    /// 断线后最大重连次数，0 表示不限次数
+    pub reconnect_max_attempts: u32,
+    /// 首次重连等待时间（毫秒）
+    pub reconnect_initial_delay_ms: u64,
+    /// 重连等待时间上限（毫秒）
+    pub reconnect_max_delay_ms: u64,
+    /// 重连后轮询未完成任务状态的间隔（毫秒）
+    pub task_poll_interval_ms: u64,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        WebSocketConfig {
+            heartbeat_interval_secs: 30,
+            reconnect_max_attempts: 10,
+            reconnect_initial_delay_ms: 1000,
+            reconnect_max_delay_ms: 30000,
+            task_poll_interval_ms: 2000,
+        }
+    }
+}
+
+/// 任务推送方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportPreference {
+    /// 优先使用 WebSocket，不可用时回退到 HTTP 轮询
+    #[default]
+    Auto,
+    /// 仅使用 WebSocket
+    Websocket,
+    /// 仅使用 HTTP 轮询（适用于屏蔽 WebSocket 升级的网络环境）
+    Polling,
+}
+
+/// 任务推送配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TransportConfig {
+    pub mode: TransportPreference,
+    /// 轮询模式下查询任务状态的间隔（毫秒）
+    pub poll_interval_ms: u64,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        TransportConfig {
+            mode: TransportPreference::Auto,
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+/// 后端健康检查配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// 检查间隔（秒）
+    pub interval_secs: u64,
+    /// 单次检查超时（毫秒），超时视为不可用
+    pub timeout_ms: u64,
+    /// 响应延迟超过该值视为降级（毫秒）
+    pub degraded_latency_ms: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig {
+            interval_secs: 15,
+            timeout_ms: 5000,
+            degraded_latency_ms: 1500,
+        }
+    }
+}
+
+/// 授权到期提醒配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct LicenseConfig {
+    /// 到期前多少天发送提醒（每档只提醒一次）
+    pub warning_days: Vec<u64>,
+    /// 授权状态检查间隔上限（秒）
+    pub check_interval_secs: u64,
+}
+
+impl Default for LicenseConfig {
+    fn default() -> Self {
+        LicenseConfig {
+            warning_days: vec![7, 1],
+            check_interval_secs: 3600,
+        }
+    }
+}
+
+/// 图片存储配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// 已下载图片的本地缓存上限（MB），超出时删除最早访问的文件
+    pub cache_max_mb: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig { cache_max_mb: 200 }
+    }
+}
+
+/// 批量检测配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct BatchConfig {
+    /// 每个任务包含的最大图片数（融合模式为图片对数），超出时拆分为多个子任务
+    pub max_images_per_task: usize,
+    /// 同时运行的子任务数量上限
+    pub max_concurrent_tasks: usize,
+    /// 递归扫描子目录的最大深度
+    pub max_depth: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig {
+            max_images_per_task: 100,
+            max_concurrent_tasks: 2,
+            max_depth: 16,
+        }
+    }
+}
+
+/// 融合模式 RGB/IR 配对规则
+///
+/// 前缀、后缀和目录名不区分大小写；正则表达式匹配不含扩展名的文件名，
+/// 命名分组 `key` 为配对标识，`modality` 分组的值与 rgb / ir 比较（不区分大小写）。
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PairingRule {
+    /// 文件名前缀，如 rgb_001.jpg / ir_001.jpg
+    Prefix { rgb: String, ir: String },
+    /// 文件名后缀（扩展名之前），如 001_rgb.jpg / 001_ir.jpg
+    Suffix { rgb: String, ir: String },
+    /// 同级目录，如 rgb/001.jpg / ir/001.jpg
+    Directory { rgb: String, ir: String },
+    /// 正则表达式
+    Regex { pattern: String, rgb: String, ir: String },
+}
+
+/// 配对配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct PairingConfig {
+    /// 按顺序尝试，使用第一条匹配的规则
+    pub rules: Vec<PairingRule>,
+}
+
+impl Default for PairingConfig {
+    fn default() -> Self {
+        PairingConfig {
+            rules: vec![
+                PairingRule::Prefix {
+                    rgb: "rgb_".to_string(),
+                    ir: "ir_".to_string(),
+                },
+                PairingRule::Suffix {
+                    rgb: "_rgb".to_string(),
+                    ir: "_ir".to_string(),
+                },
+                PairingRule::Directory {
+                    rgb: "rgb".to_string(),
+                    ir: "ir".to_string(),
+                },
+            ],
+        }
+    }
+}
+
+/// 重试配置
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 提交任务的最大尝试次数（包括第一次），1 表示不重试
+    pub max_attempts: u32,
+    /// 第一次重试前的等待时间，之后按 2 的幂增长
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// 可重试的错误类别（提交任务不是幂等的，只重试连接失败等请求未到达服务器的错误）
+    pub retryable_errors: Vec<ErrorCode>,
+    /// 可重试的后端 HTTP 状态码（除 429 外只在响应带有 Retry-After 时重试）
+    pub retryable_status: Vec<u16>,
+    /// 单张图片结果为 error 时的最大检测次数（包括第一次），1 表示不重试；只对按路径提交的任务生效
+    pub image_max_attempts: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            initial_delay_ms: 1000,
+            max_delay_ms: 10000,
+            retryable_errors: vec![ErrorCode::Network],
+            retryable_status: vec![429, 503],
+            image_max_attempts: 2,
+        }
+    }
 }
 
 /// 配置状态（用于Tauri State）
@@ -21,12 +313,12 @@ pub struct ConfigState(pub Arc<AppConfig>);
 
 impl AppConfig {
     /// 从YAML文件加载配置
-    pub fn from_yaml_file(path: &PathBuf) -> Result<Self, String> {
-        let content =
-            std::fs::read_to_string(path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    pub fn from_yaml_file(path: &PathBuf) -> Result<Self, AppError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::config(format!("读取配置文件失败: {}", e)))?;
 
-        let config: AppConfig =
-            serde_yaml::from_str(&content).map_err(|e| format!("解析YAML失败: {}", e))?;
+        let config: AppConfig = serde_yaml::from_str(&content)
+            .map_err(|e| AppError::config(format!("解析YAML失败: {}", e)))?;
 
         Ok(config)
     }
@@ -34,13 +326,13 @@ impl AppConfig {
 
 /// 获取配置文件路径
 /// 从.env文件中读取PROJECT_PATH环境变量，并拼接配置文件路径
-pub fn get_config_path() -> Result<PathBuf, String> {
+pub fn get_config_path() -> Result<PathBuf, AppError> {
     // 加载.env文件
     dotenv().ok();
 
     // 从环境变量获取项目路径
     let project_path = std::env::var("PROJECT_PATH").map_err(|_| {
-        "无法获取PROJECT_PATH环境变量，请检查.env文件是否存在并正确配置".to_string()
+        AppError::config("无法获取PROJECT_PATH环境变量，请检查.env文件是否存在并正确配置")
     })?;
 
     let config_path = PathBuf::from(project_path)
@@ -52,11 +344,11 @@ pub fn get_config_path() -> Result<PathBuf, String> {
 }
 
 /// 加载配置
-pub fn load_config() -> Result<AppConfig, String> {
+pub fn load_config() -> Result<AppConfig, AppError> {
     let config_path = get_config_path()?;
 
     if !config_path.exists() {
-        return Err(format!("配置文件不存在: {:?}", config_path));
+        return Err(AppError::config(format!("配置文件不存在: {:?}", config_path)));
     }
 
     AppConfig::from_yaml_file(&config_path)
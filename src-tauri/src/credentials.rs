@@ -0,0 +1,177 @@
+use crate::error::AppError;
+use crate::license::{self, LicenseMonitor};
+use crate::profiles::ProfileState;
+use keyring::Entry;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+/// 密钥环服务名
+const KEYRING_SERVICE: &str = "face-detection-app";
+/// 旧版本使用的单一账户名（所有服务器共用一个 API Key）
+const LEGACY_ACCOUNT: &str = "api_key";
+
+/// 服务器配置对应的密钥环账户名
+fn account_for(profile_id: &str) -> String {
+    format!("api_key:{}", profile_id)
+}
+
+fn entry_for(profile_id: &str) -> Result<Entry, AppError> {
+    Ok(Entry::new(KEYRING_SERVICE, &account_for(profile_id))?)
+}
+
+/// 授权信息（有效期、吊销状态）与 API Key 存放在同一服务下的相邻条目
+fn license_entry_for(profile_id: &str) -> Result<Entry, AppError> {
+    Ok(Entry::new(KEYRING_SERVICE, &format!("license:{}", profile_id))?)
+}
+
+/// 读取密钥环条目，不存在时返回 None
+fn read_entry(entry: &Entry) -> Result<Option<String>, AppError> {
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 删除密钥环条目，不存在时视为成功
+fn delete_entry(entry: &Entry) -> Result<(), AppError> {
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn load_profile_api_key(profile_id: &str) -> Result<Option<String>, AppError> {
+    read_entry(&entry_for(profile_id)?)
+}
+
+pub fn save_profile_api_key(profile_id: &str, api_key: &str) -> Result<(), AppError> {
+    entry_for(profile_id)?.set_password(api_key)?;
+    Ok(())
+}
+
+/// 删除 API Key 及对应的授权信息
+pub fn delete_profile_api_key(profile_id: &str) -> Result<(), AppError> {
+    delete_entry(&entry_for(profile_id)?)?;
+    delete_entry(&license_entry_for(profile_id)?)
+}
+
+/// 读取授权信息（JSON）
+pub fn load_profile_license(profile_id: &str) -> Result<Option<String>, AppError> {
+    read_entry(&license_entry_for(profile_id)?)
+}
+
+pub fn save_profile_license(profile_id: &str, license_json: &str) -> Result<(), AppError> {
+    license_entry_for(profile_id)?.set_password(license_json)?;
+    Ok(())
+}
+
+/// 迁移旧版本的单一 API Key 到当前服务器配置
+///
+/// 旧版本只有一个后端，旧条目必然属于启动时的当前服务器；
+/// 当前服务器已有 API Key 时保留新条目，仅删除旧条目。
+pub fn migrate_legacy_api_key(profiles: &ProfileState) -> Result<(), AppError> {
+    let legacy = Entry::new(KEYRING_SERVICE, LEGACY_ACCOUNT)?;
+    let Some(api_key) = read_entry(&legacy)? else {
+        return Ok(());
+    };
+
+    let profile = profiles.active_profile();
+    if load_profile_api_key(&profile.id)?.is_none() {
+        save_profile_api_key(&profile.id, &api_key)?;
+        profiles.set_api_key(&profile.id, Some(api_key));
+        log::info!("已将旧版 API Key 迁移到服务器配置：{}", profile.name);
+    }
+    delete_entry(&legacy)?;
+    Ok(())
+}
+
+/// 服务器配置的 API Key 存储情况（不包含密钥本身）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredApiKeyInfo {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub base_url: String,
+    pub has_api_key: bool,
+    pub is_active: bool,
+}
+
+/// 未指定服务器配置时使用当前服务器
+fn resolve_profile_id(profile_id: Option<String>, profiles: &ProfileState) -> Result<String, AppError> {
+    match profile_id {
+        Some(id) => Ok(profiles.get(&id)?.id),
+        None => Ok(profiles.active_profile().id),
+    }
+}
+
+// ===== API Key 安全存储（系统密钥环） =====
+
+/// 将 API Key 存储到系统密钥环（默认存到当前服务器配置下）
+#[tauri::command]
+pub fn store_api_key(
+    app: AppHandle,
+    api_key: String,
+    profile_id: Option<String>,
+    profiles: State<'_, ProfileState>,
+) -> Result<(), AppError> {
+    let profile_id = resolve_profile_id(profile_id, &profiles)?;
+    log::info!("存储服务器配置 {} 的 API Key 到系统密钥环", profile_id);
+
+    save_profile_api_key(&profile_id, &api_key)?;
+    profiles.set_api_key(&profile_id, Some(api_key));
+    // 手动录入的 API Key 没有到期信息，重新开始记录授权状态
+    license::record_activation(&app, &profile_id, None)?;
+
+    log::info!("API Key 已成功存储到系统密钥环");
+    Ok(())
+}
+
+/// 检查服务器配置是否已保存 API Key（默认检查当前服务器，不返回密钥本身）
+#[tauri::command]
+pub fn has_api_key(
+    profile_id: Option<String>,
+    profiles: State<'_, ProfileState>,
+) -> Result<bool, AppError> {
+    match profile_id {
+        Some(id) => Ok(load_profile_api_key(&profiles.get(&id)?.id)?.is_some()),
+        None => Ok(profiles.has_api_key()),
+    }
+}
+
+/// 从系统密钥环删除 API Key（默认删除当前服务器配置）
+#[tauri::command]
+pub fn delete_api_key(
+    profile_id: Option<String>,
+    license_monitor: State<'_, LicenseMonitor>,
+    profiles: State<'_, ProfileState>,
+) -> Result<(), AppError> {
+    let profile_id = resolve_profile_id(profile_id, &profiles)?;
+    log::info!("从系统密钥环删除服务器配置 {} 的 API Key", profile_id);
+
+    delete_profile_api_key(&profile_id)?;
+    profiles.set_api_key(&profile_id, None);
+    license_monitor.refresh();
+
+    log::info!("API Key 已从密钥环删除");
+    Ok(())
+}
+
+/// 列出各服务器配置的 API Key 存储情况
+#[tauri::command]
+pub fn list_api_keys(profiles: State<'_, ProfileState>) -> Result<Vec<StoredApiKeyInfo>, AppError> {
+    let config = profiles.list();
+    config
+        .profiles
+        .into_iter()
+        .map(|p| {
+            Ok(StoredApiKeyInfo {
+                has_api_key: load_profile_api_key(&p.id)?.is_some(),
+                is_active: p.id == config.active_profile_id,
+                profile_id: p.id,
+                profile_name: p.name,
+                base_url: p.base_url,
+            })
+        })
+        .collect()
+}
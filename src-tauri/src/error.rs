@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 错误类别（前端据此区分错误类型，序列化值保持稳定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 网络不可达、连接中断
+    Network,
+    /// 请求或连接超时
+    Timeout,
+    /// API Key 无效（HTTP 401）
+    Unauthorized,
+    /// 授权已过期或被吊销（HTTP 403）
+    LicenseExpired,
+    /// 资源不存在（HTTP 404）
+    NotFound,
+    /// 后端返回错误状态或无法解析的响应
+    BadResponse,
+    /// 参数校验失败
+    Validation,
+    /// 本地配置、文件或密钥环错误
+    Config,
+}
+
+/// 命令错误（所有 Tauri 命令统一返回该类型）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// 后端返回的 HTTP 状态码
+    pub status: Option<u16>,
+    /// 后端返回的原始响应内容
+    pub body: Option<String>,
+    /// 请求确定没有到达服务器（连接失败），非幂等的请求可以安全重试
+    #[serde(skip)]
+    pub not_sent: bool,
+    /// 后端通过 Retry-After 响应头要求的等待时间（秒）
+    #[serde(skip)]
+    pub retry_after: Option<u64>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            status: None,
+            body: None,
+            not_sent: false,
+            retry_after: None,
+        }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Network, message)
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Timeout, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unauthorized, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn bad_response(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::BadResponse, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message)
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Config, message)
+    }
+
+    /// 根据后端返回的 HTTP 状态码构造错误
+    pub fn from_status(status: u16, body: String) -> Self {
+        let code = match status {
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::LicenseExpired,
+            404 => ErrorCode::NotFound,
+            408 | 504 => ErrorCode::Timeout,
+            _ => ErrorCode::BadResponse,
+        };
+        Self {
+            code,
+            message: format!("服务器返回错误 ({}): {}", status, body),
+            status: Some(status),
+            body: Some(body),
+            not_sent: false,
+            retry_after: None,
+        }
+    }
+
+    /// 是否为认证类错误（API Key 无效或授权失效）
+    pub fn is_auth_error(&self) -> bool {
+        matches!(self.code, ErrorCode::Unauthorized | ErrorCode::LicenseExpired)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<reqwest::Error> for AppError {
+    fn from(value: reqwest::Error) -> Self {
+        let mut error = if value.is_timeout() {
+            AppError::timeout(format!("请求超时：{}", value))
+        } else if let Some(status) = value.status() {
+            AppError::from_status(status.as_u16(), value.to_string())
+        } else if value.is_decode() {
+            AppError::bad_response(format!("解析响应失败：{}", value))
+        } else {
+            AppError::network(format!("网络请求失败：{}", value))
+        };
+        error.not_sent = value.is_connect();
+        error
+    }
+}
+
+impl From<keyring::Error> for AppError {
+    fn from(value: keyring::Error) -> Self {
+        AppError::config(format!("密钥环操作失败：{}", value))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::api::ApiClient;
+use crate::config::{ConfigState, HealthConfig};
+use crate::error::AppError;
+use crate::profiles::ProfileState;
+use crate::tasks::now_millis;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 后端可用状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendHealthState {
+    /// 尚未完成首次检查
+    #[default]
+    Unknown,
+    Up,
+    /// 可访问但响应过慢或后端自报异常
+    Degraded,
+    Down,
+}
+
+/// 后端健康状态快照（返回给前端，同时作为 backend_health 事件负载）
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendHealth {
+    pub state: BackendHealthState,
+    pub base_url: String,
+    /// 最近一次检查的响应延迟（毫秒），请求失败时为空
+    pub latency_ms: Option<u64>,
+    /// 降级或不可用的原因
+    pub message: Option<String>,
+    pub backend_version: Option<String>,
+    /// 最近一次检查时间（Unix 毫秒）
+    pub checked_at: Option<u64>,
+    /// 状态最近一次变化的时间（Unix 毫秒）
+    pub changed_at: Option<u64>,
+}
+
+/// 最近一次健康检查结果
+#[derive(Default)]
+pub struct HealthMonitor(Mutex<BackendHealth>);
+
+impl HealthMonitor {
+    pub fn snapshot(&self) -> BackendHealth {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// 记录检查结果，返回状态是否发生变化
+    fn update(&self, mut health: BackendHealth) -> bool {
+        let mut current = self.0.lock().unwrap();
+        let changed = current.state != health.state || current.base_url != health.base_url;
+        health.changed_at = if changed {
+            health.checked_at
+        } else {
+            current.changed_at
+        };
+        *current = health;
+        changed
+    }
+}
+
+/// 启动后台健康检查任务，状态变化时发送 backend_health 事件
+pub fn spawn_health_monitor(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let config = app.state::<ConfigState>().0.health.clone();
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        log::info!("后端健康检查已启动，间隔 {}s", config.interval_secs.max(1));
+
+        loop {
+            interval.tick().await;
+            let client = app.state::<ProfileState>().public_client();
+            let health = check_health(&client, &config).await;
+            if app.state::<HealthMonitor>().update(health.clone()) {
+                log::info!(
+                    "后端状态变化：{:?}（{}）",
+                    health.state,
+                    health.message.as_deref().unwrap_or("正常")
+                );
+                let _ = app.emit("backend_health", &health);
+            }
+        }
+    });
+}
+
+/// 调用一次 /health 并根据结果和延迟判定状态
+async fn check_health(client: &ApiClient, config: &HealthConfig) -> BackendHealth {
+    let started = Instant::now();
+    let response = client.health(Duration::from_millis(config.timeout_ms.max(100))).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut health = BackendHealth {
+        base_url: client.base_url().to_string(),
+        checked_at: Some(now_millis()),
+        ..Default::default()
+    };
+
+    match response {
+        Ok(body) => {
+            health.latency_ms = Some(latency_ms);
+            health.backend_version = body.version.clone();
+            if !body.is_healthy() {
+                health.state = BackendHealthState::Degraded;
+                health.message = Some(format!("后端报告状态：{}", body.status));
+            } else if latency_ms > config.degraded_latency_ms {
+                health.state = BackendHealthState::Degraded;
+                health.message = Some(format!("响应延迟 {}ms 超过阈值", latency_ms));
+            } else {
+                health.state = BackendHealthState::Up;
+            }
+        }
+        Err(e) => {
+            health.state = BackendHealthState::Down;
+            health.message = Some(e.message);
+        }
+    }
+
+    health
+}
+
+/// 获取最近一次后端健康检查结果
+#[tauri::command]
+pub fn get_backend_health(monitor: State<'_, HealthMonitor>) -> Result<BackendHealth, AppError> {
+    Ok(monitor.snapshot())
+}
@@ -0,0 +1,545 @@
+use crate::config::{BatchConfig, ImageConfig, PreprocessConfig};
+use crate::error::AppError;
+use crate::preprocess;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::stream::{self, Stream, StreamExt};
+use image::codecs::jpeg::JpegEncoder;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// 单模态检测请求（前端传入图片路径）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SingleModePathRequest {
+    pub mode: String,
+    pub modality: String,
+    pub image_paths: Vec<String>,
+}
+
+/// 融合模式图片路径对
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePathPair {
+    pub rgb: String,
+    pub ir: String,
+}
+
+/// 融合模式检测请求（前端传入图片路径）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FusionModePathRequest {
+    pub mode: String,
+    pub pairs: Vec<ImagePathPair>,
+}
+
+// ===== 文件检查 =====
+
+/// 识别格式时读取的文件头长度
+const HEADER_LEN: u64 = 16;
+/// 查找 JPEG 结束标记时读取的文件尾长度
+const TAIL_LEN: u64 = 64 * 1024;
+
+/// 按文件内容识别出的图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectedFormat {
+    Jpeg,
+    Png,
+    Bmp,
+    Webp,
+}
+
+impl DetectedFormat {
+    /// 根据文件头（magic bytes）识别格式
+    fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(DetectedFormat::Jpeg)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            Some(DetectedFormat::Png)
+        } else if bytes.starts_with(b"BM") {
+            Some(DetectedFormat::Bmp)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(DetectedFormat::Webp)
+        } else {
+            None
+        }
+    }
+
+    /// 该格式对应的文件扩展名
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            DetectedFormat::Jpeg => &["jpg", "jpeg"],
+            DetectedFormat::Png => &["png"],
+            DetectedFormat::Bmp => &["bmp"],
+            DetectedFormat::Webp => &["webp"],
+        }
+    }
+
+    fn image_format(&self) -> ImageFormat {
+        match self {
+            DetectedFormat::Jpeg => ImageFormat::Jpeg,
+            DetectedFormat::Png => ImageFormat::Png,
+            DetectedFormat::Bmp => ImageFormat::Bmp,
+            DetectedFormat::Webp => ImageFormat::WebP,
+        }
+    }
+
+    /// 检查文件结构是否完整（结束标记或头部声明的长度），用于发现截断的文件
+    ///
+    /// 只读取文件头和文件尾；JPEG 末尾不是 EOI 时再按段结构查找 EOI。
+    fn is_complete(&self, file: &mut File, header: &[u8], size: u64) -> io::Result<bool> {
+        match self {
+            DetectedFormat::Jpeg => {
+                // 部分设备会在 EOI 之后补零
+                let tail = read_tail(file, size, TAIL_LEN)?;
+                let end = tail.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+                if tail[..end].ends_with(&[0xFF, 0xD9]) {
+                    return Ok(true);
+                }
+                // EOI 之后还可能附带其他数据（如三星的尾部信息、Google 动态照片附加的 MP4）
+                file.seek(SeekFrom::Start(0))?;
+                jpeg_has_eoi(&mut BufReader::new(file))
+            }
+            DetectedFormat::Png => {
+                let tail = read_tail(file, size, 12)?;
+                Ok(tail.len() == 12 && &tail[4..8] == b"IEND")
+            }
+            DetectedFormat::Bmp => Ok(read_u32_le(header, 2).is_some_and(|len| len as u64 <= size)),
+            DetectedFormat::Webp => Ok(read_u32_le(header, 4).is_some_and(|len| len as u64 + 8 <= size)),
+        }
+    }
+}
+
+/// 读取文件末尾最多 len 个字节
+fn read_tail(file: &mut File, size: u64, len: u64) -> io::Result<Vec<u8>> {
+    let len = len.min(size);
+    file.seek(SeekFrom::Start(size - len))?;
+    let mut tail = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut tail)?;
+    Ok(tail)
+}
+
+fn read_byte(reader: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0u8; 1];
+    match reader.read_exact(&mut byte) {
+        Ok(()) => Ok(Some(byte[0])),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// 按 JPEG 段结构查找 EOI（跳过各段内容，EXIF 缩略图中的 EOI 不会被误认）
+///
+/// 找到 EOI 即返回 true，不读取其后的附加数据；段结构异常或在 EOI 之前到达文件末尾时返回 false。
+fn jpeg_has_eoi(reader: &mut impl Read) -> io::Result<bool> {
+    let mut soi = [0u8; 2];
+    reader.read_exact(&mut soi)?;
+
+    let mut pending: Option<u8> = None;
+    loop {
+        let marker = match pending.take() {
+            Some(marker) => marker,
+            None => {
+                if read_byte(reader)? != Some(0xFF) {
+                    return Ok(false);
+                }
+                let mut marker = 0xFF;
+                while marker == 0xFF {
+                    let Some(byte) = read_byte(reader)? else {
+                        return Ok(false);
+                    };
+                    marker = byte;
+                }
+                marker
+            }
+        };
+
+        match marker {
+            0xD9 => return Ok(true),
+            // 不带长度的标记
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => {}
+        }
+
+        let mut length = [0u8; 2];
+        if reader.read_exact(&mut length).is_err() {
+            return Ok(false);
+        }
+        let length = u16::from_be_bytes(length) as u64;
+        if length < 2 || io::copy(&mut reader.by_ref().take(length - 2), &mut io::sink())? != length - 2 {
+            return Ok(false);
+        }
+        if marker != 0xDA {
+            continue;
+        }
+
+        // SOS 之后是熵编码数据：FF 00 为填充，FF D0-D7 为复位标记，其余 FF xx 为下一个标记
+        loop {
+            let Some(byte) = read_byte(reader)? else {
+                return Ok(false);
+            };
+            if byte != 0xFF {
+                continue;
+            }
+            let mut next = 0xFF;
+            while next == 0xFF {
+                let Some(byte) = read_byte(reader)? else {
+                    return Ok(false);
+                };
+                next = byte;
+            }
+            if next != 0x00 && !(0xD0..=0xD7).contains(&next) {
+                pending = Some(next);
+                break;
+            }
+        }
+    }
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 图片检查结果（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageValidationReport {
+    pub path: String,
+    /// 按文件内容识别出的格式
+    pub format: DetectedFormat,
+    pub extension: String,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: u64,
+}
+
+/// 检查图片文件
+///
+/// 依次检查：文件存在且为普通文件、扩展名受支持、不超过大小限制、
+/// 文件头与扩展名一致、文件结构完整（未被截断），并读取图片头获取尺寸检查分辨率范围。
+/// 只读取文件头、文件尾和图片头，不读取整个文件。
+pub(crate) fn check_image_file(
+    path: &Path,
+    config: &ImageConfig,
+) -> Result<ImageValidationReport, AppError> {
+    let display = path.display();
+
+    if !path.exists() {
+        return Err(AppError::validation(format!("文件不存在：{}", display)));
+    }
+
+    if !path.is_file() {
+        return Err(AppError::validation(format!("路径不是文件：{}", display)));
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .ok_or_else(|| AppError::validation(format!("无法获取文件扩展名：{}", display)))?;
+
+    if !config.supported_formats.contains(&extension) {
+        return Err(AppError::validation(format!(
+            "不支持的图片格式：{}. 支持的格式：{}",
+            extension,
+            config.supported_formats.join(", ")
+        )));
+    }
+
+    let size = std::fs::metadata(path)
+        .map_err(|e| AppError::validation(format!("读取文件信息失败：{} - {}", display, e)))?
+        .len();
+    let max_bytes = config.max_file_size_mb * 1024 * 1024;
+    if size > max_bytes {
+        return Err(AppError::validation(format!(
+            "图片大小超过限制：{}（{:.1} MB，上限 {} MB）",
+            display,
+            size as f64 / 1024.0 / 1024.0,
+            config.max_file_size_mb
+        )));
+    }
+    if size == 0 {
+        return Err(AppError::validation(format!("图片文件为空：{}", display)));
+    }
+
+    let read_error = |e: io::Error| AppError::validation(format!("读取图片失败：{} - {}", display, e));
+    let mut file = File::open(path).map_err(read_error)?;
+    let mut header = Vec::with_capacity(HEADER_LEN as usize);
+    file.by_ref().take(HEADER_LEN).read_to_end(&mut header).map_err(read_error)?;
+
+    let format = DetectedFormat::sniff(&header).ok_or_else(|| {
+        AppError::validation(format!("无法识别的图片内容（文件头不是 JPEG/PNG/BMP/WebP）：{}", display))
+    })?;
+    if !format.extensions().contains(&extension.as_str()) {
+        return Err(AppError::validation(format!(
+            "图片内容与扩展名不一致：{}（扩展名为 {}，实际为 {:?}）",
+            display, extension, format
+        )));
+    }
+    if !format.is_complete(&mut file, &header, size).map_err(read_error)? {
+        return Err(AppError::validation(format!("图片文件不完整或已损坏：{}", display)));
+    }
+
+    file.seek(SeekFrom::Start(0)).map_err(read_error)?;
+    let (width, height) = ImageReader::with_format(BufReader::new(file), format.image_format())
+        .into_dimensions()
+        .map_err(|e| AppError::validation(format!("图片头解析失败：{} - {}", display, e)))?;
+
+    if width < config.min_width || height < config.min_height {
+        return Err(AppError::validation(format!(
+            "图片分辨率过低：{}（{}x{}，最小 {}x{}）",
+            display, width, height, config.min_width, config.min_height
+        )));
+    }
+    if width > config.max_width || height > config.max_height {
+        return Err(AppError::validation(format!(
+            "图片分辨率过高：{}（{}x{}，最大 {}x{}）",
+            display, width, height, config.max_width, config.max_height
+        )));
+    }
+
+    Ok(ImageValidationReport {
+        path: path.display().to_string(),
+        format,
+        extension,
+        width,
+        height,
+        bytes: size,
+    })
+}
+
+/// 提交前检查全部图片，一次性报告所有不合格的文件
+///
+/// 需要读取文件，应在阻塞线程中调用。
+pub(crate) fn check_image_files<'a>(
+    paths: impl IntoIterator<Item = &'a str>,
+    config: &ImageConfig,
+) -> Result<(), AppError> {
+    let errors: Vec<String> = paths
+        .into_iter()
+        .filter_map(|path| check_image_file(Path::new(path), config).err())
+        .map(|e| e.message)
+        .collect();
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(AppError::validation(errors[0].clone())),
+        n => Err(AppError::validation(format!(
+            "{} 张图片未通过检查：\n{}",
+            n,
+            errors.join("\n")
+        ))),
+    }
+}
+
+// ===== 文件夹扫描 =====
+
+/// 文件夹检测请求（前端传入）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderDetectRequest {
+    pub folder_path: String,
+    pub mode: String,
+    pub modality: String,
+    /// 是否扫描子目录，默认 true
+    pub recursive: Option<bool>,
+}
+
+/// 被跳过的文件及原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedImage {
+    pub path: String,
+    pub reason: String,
+}
+
+/// 文件夹扫描结果（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderScanResult {
+    pub folder_path: String,
+    /// 通过检查的图片（按路径排序）
+    pub images: Vec<ImageValidationReport>,
+    pub skipped: Vec<SkippedImage>,
+    pub total_bytes: u64,
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_name().to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+/// 扫描文件夹中的图片并逐个检查
+///
+/// 隐藏文件和隐藏目录（如 .git）直接忽略；其余文件中扩展名不受支持、
+/// 未通过 `check_image_file` 或无法读取的都记录到 skipped 中。
+/// 遍历大量文件会阻塞，需在阻塞线程中调用。
+pub(crate) fn scan_image_folder(
+    folder: &Path,
+    recursive: bool,
+    image_config: &ImageConfig,
+    batch_config: &BatchConfig,
+) -> Result<FolderScanResult, AppError> {
+    if !folder.is_dir() {
+        return Err(AppError::validation(format!("文件夹不存在：{}", folder.display())));
+    }
+
+    let max_depth = if recursive { batch_config.max_depth.max(1) } else { 1 };
+    let mut images = Vec::new();
+    let mut skipped = Vec::new();
+
+    let walker = WalkDir::new(folder)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_hidden(entry));
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let path = e.path().map(|p| p.display().to_string()).unwrap_or_default();
+                skipped.push(SkippedImage {
+                    path,
+                    reason: format!("无法读取：{}", e),
+                });
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        let supported = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| image_config.supported_formats.contains(&ext.to_lowercase()));
+        if !supported {
+            skipped.push(SkippedImage {
+                path: path.display().to_string(),
+                reason: "不是支持的图片格式".to_string(),
+            });
+            continue;
+        }
+
+        match check_image_file(path, image_config) {
+            Ok(report) => images.push(report),
+            Err(e) => skipped.push(SkippedImage {
+                path: path.display().to_string(),
+                reason: e.message,
+            }),
+        }
+    }
+
+    log::info!(
+        "扫描文件夹 {}：{} 张图片通过检查，跳过 {} 个文件",
+        folder.display(),
+        images.len(),
+        skipped.len()
+    );
+    Ok(FolderScanResult {
+        folder_path: folder.display().to_string(),
+        total_bytes: images.iter().map(|image| image.bytes).sum(),
+        images,
+        skipped,
+    })
+}
+
+// ===== 预览 =====
+
+/// 生成本地图片的预览缩略图（按 EXIF 方向旋转，最长边不超过 max_edge），返回 JPEG data URL
+///
+/// 前端按路径选择图片后用它显示预览，WebView 不需要读取原图。
+pub(crate) fn preview_image(path: &Path, max_edge: u32) -> Result<String, AppError> {
+    let preview_error = |e: image::ImageError| AppError::validation(format!("生成图片预览失败：{}", e));
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| AppError::validation(format!("读取图片失败：{} - {}", path.display(), e)))?
+        .into_decoder()
+        .map_err(preview_error)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).map_err(preview_error)?;
+    image.apply_orientation(orientation);
+    let thumbnail = image.thumbnail(max_edge, max_edge);
+
+    let mut output = Vec::new();
+    DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut output, 80))
+        .map_err(preview_error)?;
+    Ok(format!("data:image/jpeg;base64,{}", BASE64.encode(output)))
+}
+
+// ===== 请求体 =====
+
+/// 读取图片、预处理并编码为 Base64
+async fn encode_image_file(path: PathBuf, config: &PreprocessConfig) -> io::Result<String> {
+    let bytes = tokio::fs::read(&path).await.map_err(|e| {
+        log::error!("读取图片失败：{} - {}", path.display(), e);
+        e
+    })?;
+    let bytes = preprocess::preprocess_file(bytes, config).await.map_err(|e| {
+        log::error!("{}：{}", e.message, path.display());
+        io::Error::other(e.message)
+    })?;
+    Ok(BASE64.encode(bytes))
+}
+
+/// JSON 字符串字面量（Base64 不含需要转义的字符，其余字段使用 serde_json 转义）
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// 流式生成单模态请求体：{"mode":..,"modality":..,"images":["..",..]}
+///
+/// 每次只读取、预处理并编码一张图片，避免整批图片的 Base64 同时驻留内存。
+pub(crate) fn single_request_body(
+    request: SingleModePathRequest,
+    config: PreprocessConfig,
+) -> impl Stream<Item = io::Result<Vec<u8>>> + Send + 'static {
+    let prefix = format!(
+        "{{\"mode\":{},\"modality\":{},\"images\":[",
+        json_string(&request.mode),
+        json_string(&request.modality)
+    );
+
+    let images = stream::iter(request.image_paths.into_iter().enumerate()).then(move |(index, path)| {
+        let config = config.clone();
+        async move {
+            let encoded = encode_image_file(PathBuf::from(path), &config).await?;
+            let separator = if index == 0 { "" } else { "," };
+            Ok(format!("{}\"{}\"", separator, encoded).into_bytes())
+        }
+    });
+
+    stream::once(async move { Ok(prefix.into_bytes()) })
+        .chain(images)
+        .chain(stream::once(async { Ok(b"]}".to_vec()) }))
+}
+
+/// 流式生成融合模式请求体：{"mode":..,"pairs":[{"rgb":"..","ir":".."},..]}
+pub(crate) fn fusion_request_body(
+    request: FusionModePathRequest,
+    config: PreprocessConfig,
+) -> impl Stream<Item = io::Result<Vec<u8>>> + Send + 'static {
+    let prefix = format!("{{\"mode\":{},\"pairs\":[", json_string(&request.mode));
+
+    let pairs = stream::iter(request.pairs.into_iter().enumerate()).then(move |(index, pair)| {
+        let config = config.clone();
+        async move {
+            let rgb = encode_image_file(PathBuf::from(pair.rgb), &config).await?;
+            let ir = encode_image_file(PathBuf::from(pair.ir), &config).await?;
+            let separator = if index == 0 { "" } else { "," };
+            Ok(format!("{}{{\"rgb\":\"{}\",\"ir\":\"{}\"}}", separator, rgb, ir).into_bytes())
+        }
+    });
+
+    stream::once(async move { Ok(prefix.into_bytes()) })
+        .chain(pairs)
+        .chain(stream::once(async { Ok(b"]}".to_vec()) }))
+}
@@ -1,32 +1,69 @@
+pub mod api;
+mod activation;
+mod admin;
+mod batch;
 mod config;
+mod credentials;
+pub mod error;
+mod health;
+mod image_files;
+mod license;
+mod license_file;
+mod pairing;
+mod preprocess;
+mod profiles;
+mod retry;
 mod shortcuts;
+mod storage;
+mod tasks;
+mod transport;
 mod util;
+mod ws;
 
+use admin::{
+    admin_login, admin_logout, create_activation_codes, get_admin_session, get_queue_status,
+    list_activation_codes, revoke_activation_code, update_activation_code, AdminState,
+};
+use batch::{
+    cancel_batch_job, get_batch_job, list_batch_jobs, pause_batch_job, resume_batch_job, BatchJobs,
+};
 use config::{load_config, ConfigState};
+use credentials::{delete_api_key, has_api_key, list_api_keys, store_api_key};
+use health::{get_backend_health, HealthMonitor};
+use license::{get_license_status, LicenseMonitor};
+use pairing::{pair_image_folder, pair_images};
+use profiles::{add_profile, delete_profile, list_profiles, switch_profile, update_profile, ProfileState};
 use shortcuts::{get_shortcuts_config, save_shortcuts_config_command};
 use std::sync::Arc;
+use storage::{
+    delete_stored_image, fetch_stored_image, fetch_task_image, get_task_images,
+    list_stored_images, upload_image, ImageCache,
+};
 use tokio::sync::Mutex;
 use tauri::Manager;
 use util::{
     activate_license,
     cancel_detection,
-    connect_websocket,
     delete_history,
+    detect_folder,
     detect_fusion_mode_async,
+    detect_fusion_mode_paths,
     detect_single_mode_async,
+    detect_single_mode_paths,
     get_all_history,
     get_history_stats,
+    get_history_task,
+    get_image_preview,
     get_supported_formats,
-    get_ws_status,
+    get_task_status,
+    list_active_tasks,
     query_history,
+    scan_image_folder,
     validate_image,
-    store_api_key,
-    retrieve_api_key,
-    delete_api_key,
-    WsConnectionStateRef,
+    HistoryCache,
 };
-use reqwest::Client;
-use std::time::Duration;
+use tasks::{clear_tasks, get_task, list_tasks, TaskRegistry};
+use ws::{connect_websocket, get_ws_status, WsConnectionStateRef};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -42,37 +79,27 @@ pub fn run() {
         app_config.image.supported_formats
     );
 
-    // 从环境变量读取 HTTP 客户端超时配置（单位：秒）
-    let request_timeout_secs = std::env::var("HTTP_REQUEST_TIMEOUT")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(30);
-    let connect_timeout_secs = std::env::var("HTTP_CONNECT_TIMEOUT")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(10);
-
-    log::info!(
-        "HTTP 客户端超时配置：请求超时={}s, 连接超时={}s",
-        request_timeout_secs,
-        connect_timeout_secs
-    );
-
-    // 创建带有超时配置的 HTTP 客户端
-    let http_client = Client::builder()
-        .timeout(Duration::from_secs(request_timeout_secs))
-        .connect_timeout(Duration::from_secs(connect_timeout_secs))
-        .build()
-        .expect("Failed to create HTTP client");
+    // 加载服务器配置，创建指向当前服务器的 HTTP 客户端
+    // 各接口的 401/403 错误通过该通道上报给授权监控
+    let (auth_error_tx, auth_error_rx) = tokio::sync::mpsc::unbounded_channel();
+    let profile_state = ProfileState::load(auth_error_tx);
 
     // 初始化 WebSocket 连接状态
-    let ws_state: WsConnectionStateRef = Arc::new(Mutex::new(util::WsConnectionState::new()));
+    let ws_state: WsConnectionStateRef = Arc::new(Mutex::new(ws::WsConnectionState::new()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .manage(http_client)
+        .plugin(tauri_plugin_dialog::init())
+        .manage(profile_state)
         .manage(ConfigState(Arc::new(app_config)))
         .manage(ws_state)
+        .manage(TaskRegistry::default())
+        .manage(BatchJobs::default())
+        .manage(HealthMonitor::default())
+        .manage(LicenseMonitor::default())
+        .manage(AdminState::default())
+        .manage(HistoryCache::default())
+        .manage(ImageCache::default())
         .setup(|app| {
             // 初始化快捷键配置
             let default_config = shortcuts::ShortcutConfig::default();
@@ -81,26 +108,80 @@ pub fn run() {
                 default_config.clone()
             });
             app.manage(shortcuts::ShortcutConfigState::new(config));
+
+            // 迁移旧版本的单一 API Key 到当前服务器配置
+            if let Err(e) = credentials::migrate_legacy_api_key(&app.state::<ProfileState>()) {
+                log::warn!("迁移旧版 API Key 失败：{}", e);
+            }
+
+            // 启动授权状态监控
+            license::spawn_license_monitor(app.handle(), auth_error_rx);
+
+            // 启动后端健康检查
+            health::spawn_health_monitor(app.handle());
+
+            // 恢复上次未完成的批量任务
+            batch::resume_persisted_jobs(app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             detect_single_mode_async,
             detect_fusion_mode_async,
+            detect_single_mode_paths,
+            detect_fusion_mode_paths,
+            scan_image_folder,
+            detect_folder,
+            get_batch_job,
+            list_batch_jobs,
+            pause_batch_job,
+            resume_batch_job,
+            cancel_batch_job,
+            pair_images,
+            pair_image_folder,
             cancel_detection,
+            get_task_status,
+            list_active_tasks,
             get_supported_formats,
             validate_image,
+            get_image_preview,
             connect_websocket,
             get_ws_status,
+            get_backend_health,
+            list_profiles,
+            add_profile,
+            update_profile,
+            delete_profile,
+            switch_profile,
+            list_tasks,
+            get_task,
+            clear_tasks,
             activate_license,
+            get_license_status,
+            admin_login,
+            admin_logout,
+            get_admin_session,
+            create_activation_codes,
+            list_activation_codes,
+            update_activation_code,
+            revoke_activation_code,
+            get_queue_status,
             query_history,
             get_history_stats,
+            get_history_task,
             delete_history,
             get_all_history,
+            upload_image,
+            list_stored_images,
+            fetch_stored_image,
+            delete_stored_image,
+            get_task_images,
+            fetch_task_image,
             get_shortcuts_config,
             save_shortcuts_config_command,
             store_api_key,
-            retrieve_api_key,
+            has_api_key,
             delete_api_key,
+            list_api_keys,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,308 @@
+use crate::config::{ConfigState, LicenseConfig};
+use crate::credentials;
+use crate::error::{AppError, ErrorCode};
+use crate::profiles::ProfileState;
+use crate::tasks::now_millis;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Notify;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// 授权状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseState {
+    /// 当前服务器尚未激活
+    Unlicensed,
+    Active,
+    /// 距离到期不足最早一档提醒天数
+    ExpiringSoon,
+    Expired,
+    /// 后端返回 401/403，API Key 已被吊销或失效
+    Revoked,
+}
+
+/// 授权信息（与 API Key 一起保存在密钥环中）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseRecord {
+    /// 后端返回的到期时间，为空表示长期有效
+    pub expires_at: Option<String>,
+    /// 激活时间（Unix 毫秒）
+    pub activated_at: u64,
+    /// 被判定为吊销的时间（Unix 毫秒）
+    pub revoked_at: Option<u64>,
+    pub revoked_reason: Option<String>,
+}
+
+impl LicenseRecord {
+    fn load(profile_id: &str) -> Option<LicenseRecord> {
+        match credentials::load_profile_license(profile_id) {
+            Ok(Some(json)) => serde_json::from_str(&json)
+                .map_err(|e| log::warn!("解析授权信息失败：{}", e))
+                .ok(),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("读取授权信息失败：{}", e);
+                None
+            }
+        }
+    }
+
+    fn save(&self, profile_id: &str) -> Result<(), AppError> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| AppError::config(format!("序列化授权信息失败：{}", e)))?;
+        credentials::save_profile_license(profile_id, &json)
+    }
+}
+
+/// 授权状态（返回给前端，同时作为 license_state_changed 事件负载）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseStatus {
+    pub state: LicenseState,
+    pub profile_id: String,
+    pub expires_at: Option<String>,
+    /// 剩余有效时间（秒），长期有效或未激活时为空
+    pub remaining_secs: Option<i64>,
+    /// 剩余天数（向下取整）
+    pub remaining_days: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// 到期提醒事件（license_expiry_warning）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseExpiryWarning {
+    pub profile_id: String,
+    /// 触发的提醒档位（到期前天数）
+    pub days_before: u64,
+    pub expires_at: String,
+    pub remaining_secs: i64,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    last_status: Option<(String, LicenseState)>,
+    /// 已发送过的提醒：(服务器配置 ID, 到期时间, 档位)
+    warned: HashSet<(String, String, u64)>,
+}
+
+/// 授权监控状态（用于 Tauri State）
+#[derive(Default)]
+pub struct LicenseMonitor {
+    state: Mutex<MonitorState>,
+    /// 授权信息或当前服务器变化时唤醒监控任务
+    wake: Notify,
+}
+
+impl LicenseMonitor {
+    /// 授权信息变化后立即重新计算状态
+    pub fn refresh(&self) {
+        self.wake.notify_one();
+    }
+}
+
+/// 解析后端返回的到期时间（RFC 3339、无时区的 ISO 8601 按 UTC 处理、仅日期按当天结束处理）
+pub(crate) fn parse_expires_at(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .map(|dt| dt.and_utc())
+}
+
+/// 计算当前服务器的授权状态
+fn current_status(app: &AppHandle, config: &LicenseConfig) -> LicenseStatus {
+    let profiles = app.state::<ProfileState>();
+    let profile_id = profiles.active_profile().id;
+
+    let mut status = LicenseStatus {
+        state: LicenseState::Unlicensed,
+        profile_id: profile_id.clone(),
+        expires_at: None,
+        remaining_secs: None,
+        remaining_days: None,
+        message: None,
+    };
+    if !profiles.has_api_key() {
+        return status;
+    }
+
+    let record = LicenseRecord::load(&profile_id).unwrap_or_default();
+    status.expires_at = record.expires_at.clone();
+    status.state = LicenseState::Active;
+
+    if record.revoked_at.is_some() {
+        status.state = LicenseState::Revoked;
+        status.message = record.revoked_reason;
+        return status;
+    }
+
+    let Some(ref expires_at) = record.expires_at else {
+        return status;
+    };
+    let Some(expiry) = parse_expires_at(expires_at) else {
+        log::warn!("无法解析授权到期时间：{}", expires_at);
+        return status;
+    };
+
+    let remaining = (expiry - Utc::now()).num_seconds();
+    status.remaining_secs = Some(remaining.max(0));
+    status.remaining_days = Some(remaining.max(0) / SECS_PER_DAY);
+    let earliest_warning = config.warning_days.iter().copied().max().unwrap_or(0) as i64;
+
+    if remaining <= 0 {
+        status.state = LicenseState::Expired;
+        status.message = Some("授权已过期，请重新激活".to_string());
+    } else if remaining <= earliest_warning * SECS_PER_DAY {
+        status.state = LicenseState::ExpiringSoon;
+        status.message = Some(format!("授权将在 {} 天后到期", remaining / SECS_PER_DAY));
+    }
+    status
+}
+
+/// 计算状态并发送事件，返回下一次需要检查的等待时间
+fn evaluate(app: &AppHandle, config: &LicenseConfig) -> Duration {
+    let status = current_status(app, config);
+    let monitor = app.state::<LicenseMonitor>();
+    let mut state = monitor.state.lock().unwrap();
+    let check_interval = config.check_interval_secs.max(1) as i64;
+
+    let key = (status.profile_id.clone(), status.state);
+    if state.last_status.as_ref() != Some(&key) {
+        log::info!("授权状态变化：{:?}（服务器配置 {}）", status.state, status.profile_id);
+        let _ = app.emit("license_state_changed", &status);
+        state.last_status = Some(key);
+    }
+
+    let (Some(remaining), Some(expires_at)) = (status.remaining_secs, status.expires_at.clone()) else {
+        return Duration::from_secs(check_interval as u64);
+    };
+    if status.state == LicenseState::Revoked || remaining <= 0 {
+        return Duration::from_secs(check_interval as u64);
+    }
+
+    // 已进入的提醒档位中只提醒最近的一档，其余档位标记为已提醒
+    let mut crossed: Vec<u64> = config
+        .warning_days
+        .iter()
+        .copied()
+        .filter(|days| remaining <= *days as i64 * SECS_PER_DAY)
+        .collect();
+    crossed.sort_unstable();
+    let newly_crossed: Vec<u64> = crossed
+        .iter()
+        .copied()
+        .filter(|days| !state.warned.contains(&(status.profile_id.clone(), expires_at.clone(), *days)))
+        .collect();
+    if let Some(days_before) = newly_crossed.first().copied() {
+        log::warn!("授权将在 {} 秒后到期（{} 天提醒）", remaining, days_before);
+        let _ = app.emit(
+            "license_expiry_warning",
+            &LicenseExpiryWarning {
+                profile_id: status.profile_id.clone(),
+                days_before,
+                expires_at: expires_at.clone(),
+                remaining_secs: remaining,
+            },
+        );
+    }
+    for days in newly_crossed {
+        state.warned.insert((status.profile_id.clone(), expires_at.clone(), days));
+    }
+
+    // 下一次检查：最近的未到达提醒档位或到期时间，不超过检查间隔
+    let next_event = config
+        .warning_days
+        .iter()
+        .map(|days| remaining - *days as i64 * SECS_PER_DAY)
+        .filter(|secs| *secs > 0)
+        .chain(std::iter::once(remaining))
+        .min()
+        .unwrap_or(check_interval);
+    Duration::from_secs(next_event.clamp(1, check_interval) as u64)
+}
+
+/// 将返回 401/403 的请求所属服务器的授权标记为已吊销
+///
+/// 切换服务器后旧服务器上的请求才返回时，只影响旧服务器的授权记录。
+fn mark_revoked(app: &AppHandle, profile_id: &str, error: &AppError) {
+    let mut record = LicenseRecord::load(profile_id).unwrap_or_default();
+    if record.revoked_at.is_some() {
+        return;
+    }
+
+    let reason = match error.code {
+        ErrorCode::LicenseExpired => "授权已过期或被吊销（HTTP 403）",
+        _ => "API Key 无效或已被吊销（HTTP 401）",
+    };
+    log::warn!("服务器配置 {} 的授权失效：{}", profile_id, error);
+    record.revoked_at = Some(now_millis());
+    record.revoked_reason = Some(reason.to_string());
+    if let Err(e) = record.save(profile_id) {
+        log::error!("保存授权信息失败：{}", e);
+    }
+    if profile_id == app.state::<ProfileState>().active_profile().id {
+        app.state::<LicenseMonitor>().refresh();
+    }
+}
+
+/// 记录激活结果（保存到期时间并清除吊销标记），由激活命令调用
+pub fn record_activation(
+    app: &AppHandle,
+    profile_id: &str,
+    expires_at: Option<String>,
+) -> Result<(), AppError> {
+    LicenseRecord {
+        expires_at,
+        activated_at: now_millis(),
+        revoked_at: None,
+        revoked_reason: None,
+    }
+    .save(profile_id)?;
+    app.state::<LicenseMonitor>().refresh();
+    Ok(())
+}
+
+/// 启动授权监控任务
+///
+/// 按到期提醒档位和到期时间安排下一次检查，并接收各接口上报的 401/403 错误。
+pub fn spawn_license_monitor(app: &AppHandle, mut auth_errors: UnboundedReceiver<(String, AppError)>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let config = app.state::<ConfigState>().0.license.clone();
+        loop {
+            let delay = evaluate(&app, &config);
+            let monitor = app.state::<LicenseMonitor>();
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = monitor.wake.notified() => {}
+                Some((profile_id, error)) = auth_errors.recv() => mark_revoked(&app, &profile_id, &error),
+            }
+        }
+    });
+}
+
+/// 获取当前服务器的授权状态和剩余有效期
+#[tauri::command]
+pub fn get_license_status(
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+) -> Result<LicenseStatus, AppError> {
+    Ok(current_status(&app, &config.0.license))
+}
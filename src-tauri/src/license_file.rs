@@ -0,0 +1,273 @@
+use crate::license::parse_expires_at;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::Utc;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 支持的授权文件格式版本
+const LICENSE_VERSION: u32 = 1;
+
+/// 构建时通过 LICENSE_PUBLIC_KEY 环境变量嵌入的授权公钥（32 字节原始公钥的 Base64）
+const EMBEDDED_PUBLIC_KEY: Option<&str> = option_env!("LICENSE_PUBLIC_KEY");
+
+/// 测试公钥，仅调试构建信任（对应私钥 license/test_private.pem 已公开，不能用于发布版本）
+#[cfg(debug_assertions)]
+const TEST_PUBLIC_KEY: Option<&str> = Some(include_str!("../license/test_public.key"));
+#[cfg(not(debug_assertions))]
+const TEST_PUBLIC_KEY: Option<&str> = None;
+
+/// 授权文件被拒绝的原因（序列化值供前端区分提示）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum LicenseFileError {
+    /// 文件不是有效的授权文件（JSON 或 Base64 解析失败）
+    #[serde(rename_all = "camelCase")]
+    InvalidFormat { detail: String },
+    /// 当前版本未嵌入授权公钥，无法验证离线授权
+    NoTrustedKey,
+    /// 签名校验失败（文件被篡改或不是由授权方签发）
+    InvalidSignature,
+    #[serde(rename_all = "camelCase")]
+    UnsupportedVersion { version: u32 },
+    /// 授权内容缺少 API Key
+    MissingApiKey,
+    #[serde(rename_all = "camelCase")]
+    InvalidExpiry { expires_at: String },
+    #[serde(rename_all = "camelCase")]
+    Expired { expires_at: String },
+    /// 授权绑定的服务器地址与当前服务器配置不一致
+    #[serde(rename_all = "camelCase")]
+    ServerMismatch { licensed: String, current: String },
+}
+
+impl fmt::Display for LicenseFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LicenseFileError::InvalidFormat { detail } => {
+                write!(f, "授权文件格式不正确：{}", detail)
+            }
+            LicenseFileError::NoTrustedKey => write!(f, "当前版本不支持离线授权（未配置授权公钥）"),
+            LicenseFileError::InvalidSignature => write!(f, "授权文件签名无效，文件可能已被修改"),
+            LicenseFileError::UnsupportedVersion { version } => {
+                write!(f, "不支持的授权文件版本：{}", version)
+            }
+            LicenseFileError::MissingApiKey => write!(f, "授权文件中缺少 API Key"),
+            LicenseFileError::InvalidExpiry { expires_at } => {
+                write!(f, "授权文件的到期时间无法识别：{}", expires_at)
+            }
+            LicenseFileError::Expired { expires_at } => {
+                write!(f, "授权文件已于 {} 过期", expires_at)
+            }
+            LicenseFileError::ServerMismatch { licensed, current } => {
+                write!(f, "授权文件仅适用于服务器 {}，当前服务器为 {}", licensed, current)
+            }
+        }
+    }
+}
+
+/// 授权文件（payload 为授权内容 JSON 的 Base64，signature 为对 payload 原始字节的 Ed25519 签名）
+#[derive(Debug, Deserialize)]
+struct SignedLicenseFile {
+    payload: String,
+    signature: String,
+}
+
+/// 授权内容
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineLicense {
+    pub version: u32,
+    pub license_id: String,
+    #[serde(default)]
+    pub customer: String,
+    pub api_key: String,
+    pub issued_at: String,
+    /// 为空表示长期有效
+    pub expires_at: Option<String>,
+    /// 绑定的服务器地址，为空表示不限制
+    pub base_url: Option<String>,
+}
+
+/// 受信任的授权公钥
+fn trusted_keys() -> Vec<VerifyingKey> {
+    [EMBEDDED_PUBLIC_KEY, TEST_PUBLIC_KEY]
+        .into_iter()
+        .flatten()
+        .filter_map(|encoded| {
+            let bytes = BASE64.decode(encoded.trim()).ok()?;
+            let bytes: [u8; 32] = bytes.try_into().ok()?;
+            VerifyingKey::from_bytes(&bytes)
+                .map_err(|e| log::error!("授权公钥无效：{}", e))
+                .ok()
+        })
+        .collect()
+}
+
+fn normalize_base_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+impl OfflineLicense {
+    /// 解析并验证授权文件内容
+    ///
+    /// 依次校验签名、格式版本、API Key、到期时间，以及绑定的服务器地址（如有）。
+    pub fn verify(content: &str, current_base_url: &str) -> Result<Self, LicenseFileError> {
+        let invalid_format = |detail: String| LicenseFileError::InvalidFormat { detail };
+
+        let file: SignedLicenseFile = serde_json::from_str(content.trim())
+            .map_err(|e| invalid_format(e.to_string()))?;
+        let payload = BASE64
+            .decode(file.payload.trim())
+            .map_err(|e| invalid_format(format!("payload 不是有效的 Base64：{}", e)))?;
+        let signature = BASE64
+            .decode(file.signature.trim())
+            .map_err(|e| invalid_format(format!("signature 不是有效的 Base64：{}", e)))?;
+        let signature = Signature::from_slice(&signature)
+            .map_err(|_| LicenseFileError::InvalidSignature)?;
+
+        let keys = trusted_keys();
+        if keys.is_empty() {
+            return Err(LicenseFileError::NoTrustedKey);
+        }
+        if !keys
+            .iter()
+            .any(|key| key.verify_strict(&payload, &signature).is_ok())
+        {
+            return Err(LicenseFileError::InvalidSignature);
+        }
+
+        let license: OfflineLicense = serde_json::from_slice(&payload)
+            .map_err(|e| invalid_format(format!("授权内容解析失败：{}", e)))?;
+
+        if license.version != LICENSE_VERSION {
+            return Err(LicenseFileError::UnsupportedVersion {
+                version: license.version,
+            });
+        }
+        if license.api_key.trim().is_empty() {
+            return Err(LicenseFileError::MissingApiKey);
+        }
+        if let Some(ref expires_at) = license.expires_at {
+            let expiry = parse_expires_at(expires_at).ok_or_else(|| {
+                LicenseFileError::InvalidExpiry {
+                    expires_at: expires_at.clone(),
+                }
+            })?;
+            if expiry <= Utc::now() {
+                return Err(LicenseFileError::Expired {
+                    expires_at: expires_at.clone(),
+                });
+            }
+        }
+        if let Some(ref licensed) = license.base_url {
+            if normalize_base_url(licensed) != normalize_base_url(current_base_url) {
+                return Err(LicenseFileError::ServerMismatch {
+                    licensed: licensed.clone(),
+                    current: current_base_url.to_string(),
+                });
+            }
+        }
+
+        Ok(license)
+    }
+}
+
+/// 使用 license/fixtures 中的样例授权文件测试验证逻辑（测试公钥只在调试构建中受信任）
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    const BASE_URL: &str = "http://127.0.0.1:8000";
+
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("license/fixtures")
+            .join(name);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("读取 {} 失败：{}", path.display(), e))
+    }
+
+    /// 每个样例文件及其预期的验证结果（Ok 为授权 ID）
+    fn expected() -> Vec<(&'static str, Result<&'static str, LicenseFileError>)> {
+        vec![
+            ("valid.license.json", Ok("TEST-VALID")),
+            ("perpetual.license.json", Ok("TEST-PERPETUAL")),
+            (
+                "expired.license.json",
+                Err(LicenseFileError::Expired {
+                    expires_at: "2020-01-01T00:00:00Z".to_string(),
+                }),
+            ),
+            (
+                "other-server.license.json",
+                Err(LicenseFileError::ServerMismatch {
+                    licensed: "http://10.0.0.1:8000".to_string(),
+                    current: BASE_URL.to_string(),
+                }),
+            ),
+            ("tampered.license.json", Err(LicenseFileError::InvalidSignature)),
+            ("wrong-key.license.json", Err(LicenseFileError::InvalidSignature)),
+        ]
+    }
+
+    #[test]
+    fn every_fixture_is_covered() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("license/fixtures");
+        let on_disk: BTreeSet<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        let covered: BTreeSet<String> = expected().iter().map(|(name, _)| name.to_string()).collect();
+        assert_eq!(on_disk, covered);
+    }
+
+    #[test]
+    fn fixtures_verify_as_expected() {
+        for (name, expected) in expected() {
+            let result = OfflineLicense::verify(&fixture(name), BASE_URL);
+            match expected {
+                Ok(license_id) => {
+                    let license = result.unwrap_or_else(|e| panic!("{} 应验证通过：{}", name, e));
+                    assert_eq!(license.license_id, license_id, "{}", name);
+                    assert_eq!(license.api_key, "sk_offline_test_0123456789abcdef", "{}", name);
+                }
+                Err(error) => assert_eq!(result.unwrap_err(), error, "{}", name),
+            }
+        }
+    }
+
+    #[test]
+    fn perpetual_license_has_no_expiry_or_server() {
+        let license = OfflineLicense::verify(&fixture("perpetual.license.json"), BASE_URL).unwrap();
+        assert_eq!(license.expires_at, None);
+        assert_eq!(license.base_url, None);
+    }
+
+    #[test]
+    fn bound_license_matches_normalized_server_url() {
+        let content = fixture("other-server.license.json");
+        let license = OfflineLicense::verify(&content, "HTTP://10.0.0.1:8000/").unwrap();
+        assert_eq!(license.license_id, "TEST-OTHER-SERVER");
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(matches!(
+            OfflineLicense::verify("not json", BASE_URL),
+            Err(LicenseFileError::InvalidFormat { .. })
+        ));
+        assert!(matches!(
+            OfflineLicense::verify(r#"{"payload":"@@","signature":""}"#, BASE_URL),
+            Err(LicenseFileError::InvalidFormat { .. })
+        ));
+        let mut file: serde_json::Value = serde_json::from_str(&fixture("valid.license.json")).unwrap();
+        file["signature"] = serde_json::Value::String(BASE64.encode([0u8; 10]));
+        assert_eq!(
+            OfflineLicense::verify(&file.to_string(), BASE_URL).unwrap_err(),
+            LicenseFileError::InvalidSignature
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::config::{ConfigState, PairingConfig, PairingRule};
+use crate::error::AppError;
+use crate::image_files::{self, SkippedImage};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::State;
+
+/// 图片模态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Rgb,
+    Ir,
+}
+
+/// 配对成功的图片
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedPair {
+    /// 配对标识（所在目录 + 去掉模态标记后的文件名）
+    pub key: String,
+    pub rgb: String,
+    pub ir: String,
+}
+
+/// 未能配对的图片
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingOrphan {
+    pub path: String,
+    /// 无法识别模态时为空
+    pub modality: Option<Modality>,
+    pub reason: String,
+}
+
+/// 同一配对标识下有多张 RGB 或多张 IR 图片
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingAmbiguity {
+    pub key: String,
+    pub rgb: Vec<String>,
+    pub ir: Vec<String>,
+}
+
+/// 配对结果（返回给前端）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingResult {
+    pub pairs: Vec<MatchedPair>,
+    pub orphans: Vec<PairingOrphan>,
+    pub ambiguities: Vec<PairingAmbiguity>,
+    /// 扫描文件夹时未通过检查的文件
+    pub skipped: Vec<SkippedImage>,
+}
+
+// ===== 配对引擎 =====
+
+enum Matcher {
+    Prefix { rgb: String, ir: String },
+    Suffix { rgb: String, ir: String },
+    Directory { rgb: String, ir: String },
+    Regex { regex: Regex, rgb: String, ir: String },
+}
+
+/// 按配置的规则识别图片模态并配对
+pub(crate) struct PairingEngine {
+    matchers: Vec<Matcher>,
+}
+
+/// 配对标识：所在目录 + 文件名标识，统一小写
+fn pair_key(dir: Option<&Path>, name: &str) -> String {
+    match dir {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.join(name).display().to_string().to_lowercase(),
+        _ => name.to_lowercase(),
+    }
+}
+
+impl PairingEngine {
+    /// 编译配对规则，正则表达式无效或缺少命名分组时返回配置错误
+    pub(crate) fn new(config: &PairingConfig) -> Result<Self, AppError> {
+        let matchers = config
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(match rule {
+                    PairingRule::Prefix { rgb, ir } => Matcher::Prefix {
+                        rgb: rgb.to_lowercase(),
+                        ir: ir.to_lowercase(),
+                    },
+                    PairingRule::Suffix { rgb, ir } => Matcher::Suffix {
+                        rgb: rgb.to_lowercase(),
+                        ir: ir.to_lowercase(),
+                    },
+                    PairingRule::Directory { rgb, ir } => Matcher::Directory {
+                        rgb: rgb.to_lowercase(),
+                        ir: ir.to_lowercase(),
+                    },
+                    PairingRule::Regex { pattern, rgb, ir } => {
+                        let regex = Regex::new(pattern).map_err(|e| {
+                            AppError::config(format!("配对规则中的正则表达式无效：{} - {}", pattern, e))
+                        })?;
+                        let names: Vec<&str> = regex.capture_names().flatten().collect();
+                        if !names.contains(&"key") || !names.contains(&"modality") {
+                            return Err(AppError::config(format!(
+                                "配对规则中的正则表达式缺少命名分组 key 或 modality：{}",
+                                pattern
+                            )));
+                        }
+                        Matcher::Regex {
+                            regex,
+                            rgb: rgb.to_lowercase(),
+                            ir: ir.to_lowercase(),
+                        }
+                    }
+                })
+            })
+            .collect::<Result<_, AppError>>()?;
+        Ok(PairingEngine { matchers })
+    }
+
+    /// 识别图片的模态和配对标识，按顺序使用第一条匹配的规则
+    fn classify(&self, path: &Path) -> Option<(Modality, String)> {
+        let stem = path.file_stem()?.to_str()?;
+        let lower = stem.to_lowercase();
+        let parent = path.parent();
+
+        self.matchers.iter().find_map(|matcher| match matcher {
+            Matcher::Prefix { rgb, ir } => [(rgb, Modality::Rgb), (ir, Modality::Ir)]
+                .into_iter()
+                .find_map(|(marker, modality)| {
+                    let rest = lower.strip_prefix(marker.as_str())?;
+                    (!rest.is_empty()).then(|| (modality, pair_key(parent, rest)))
+                }),
+            Matcher::Suffix { rgb, ir } => [(rgb, Modality::Rgb), (ir, Modality::Ir)]
+                .into_iter()
+                .find_map(|(marker, modality)| {
+                    let rest = lower.strip_suffix(marker.as_str())?;
+                    (!rest.is_empty()).then(|| (modality, pair_key(parent, rest)))
+                }),
+            Matcher::Directory { rgb, ir } => {
+                let dir_name = parent?.file_name()?.to_str()?.to_lowercase();
+                let modality = if dir_name == *rgb {
+                    Modality::Rgb
+                } else if dir_name == *ir {
+                    Modality::Ir
+                } else {
+                    return None;
+                };
+                Some((modality, pair_key(parent?.parent(), &lower)))
+            }
+            Matcher::Regex { regex, rgb, ir } => {
+                let captures = regex.captures(stem)?;
+                let key = captures.name("key")?.as_str();
+                let value = captures.name("modality")?.as_str().to_lowercase();
+                let modality = if value == *rgb {
+                    Modality::Rgb
+                } else if value == *ir {
+                    Modality::Ir
+                } else {
+                    return None;
+                };
+                (!key.is_empty()).then(|| (modality, pair_key(parent, key)))
+            }
+        })
+    }
+
+    /// 对一组图片路径（或文件名）配对
+    pub(crate) fn pair<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> PairingResult {
+        let mut result = PairingResult::default();
+        let mut groups: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+        // 与配对标识一样不区分大小写：仅大小写不同的路径视为重复，不参与配对
+        let mut seen: BTreeMap<String, &str> = BTreeMap::new();
+
+        for path in paths {
+            if let Some(first) = seen.get(&path.to_lowercase()) {
+                if *first != path {
+                    result.orphans.push(PairingOrphan {
+                        path: path.to_string(),
+                        modality: None,
+                        reason: format!("与 {} 仅大小写不同，视为重复图片", first),
+                    });
+                }
+                continue;
+            }
+            seen.insert(path.to_lowercase(), path);
+            match self.classify(Path::new(path)) {
+                Some((modality, key)) => {
+                    let group = groups.entry(key).or_default();
+                    match modality {
+                        Modality::Rgb => group.0.push(path.to_string()),
+                        Modality::Ir => group.1.push(path.to_string()),
+                    }
+                }
+                None => result.orphans.push(PairingOrphan {
+                    path: path.to_string(),
+                    modality: None,
+                    reason: "无法从文件名或目录识别模态".to_string(),
+                }),
+            }
+        }
+
+        for (key, (mut rgb, mut ir)) in groups {
+            match (rgb.len(), ir.len()) {
+                (1, 1) => result.pairs.push(MatchedPair {
+                    key,
+                    rgb: rgb.remove(0),
+                    ir: ir.remove(0),
+                }),
+                (_, 0) => result.orphans.extend(rgb.into_iter().map(|path| PairingOrphan {
+                    path,
+                    modality: Some(Modality::Rgb),
+                    reason: "缺少对应的 IR 图片".to_string(),
+                })),
+                (0, _) => result.orphans.extend(ir.into_iter().map(|path| PairingOrphan {
+                    path,
+                    modality: Some(Modality::Ir),
+                    reason: "缺少对应的 RGB 图片".to_string(),
+                })),
+                _ => result.ambiguities.push(PairingAmbiguity { key, rgb, ir }),
+            }
+        }
+
+        log::info!(
+            "图片配对完成：{} 对，未配对 {} 张，歧义 {} 组",
+            result.pairs.len(),
+            result.orphans.len(),
+            result.ambiguities.len()
+        );
+        result
+    }
+}
+
+// ===== 命令 =====
+
+/// 对一组图片路径或文件名配对（融合模式提交前调用）
+#[tauri::command]
+pub fn pair_images(paths: Vec<String>, config: State<'_, ConfigState>) -> Result<PairingResult, AppError> {
+    let engine = PairingEngine::new(&config.0.pairing)?;
+    Ok(engine.pair(paths.iter().map(String::as_str)))
+}
+
+/// 扫描文件夹并配对通过检查的图片
+#[tauri::command]
+pub async fn pair_image_folder(
+    folder_path: String,
+    recursive: Option<bool>,
+    config: State<'_, ConfigState>,
+) -> Result<PairingResult, AppError> {
+    let engine = PairingEngine::new(&config.0.pairing)?;
+    let config = config.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let scan = image_files::scan_image_folder(
+            Path::new(&folder_path),
+            recursive.unwrap_or(true),
+            &config.image,
+            &config.batch,
+        )?;
+        let mut result = engine.pair(scan.images.iter().map(|image| image.path.as_str()));
+        result.skipped = scan.skipped;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| AppError::validation(format!("扫描文件夹失败：{}", e)))?
+}
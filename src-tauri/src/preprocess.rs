@@ -0,0 +1,229 @@
+use crate::api::{FusionModeRequest, SingleModeRequest};
+use crate::config::{PreprocessConfig, PreprocessFormat};
+use crate::error::AppError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::codecs::bmp::BmpEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageEncoder, ImageFormat, ImageReader, ImageResult};
+use std::io::Cursor;
+
+/// 提交前预处理图片：按 EXIF 方向旋转、缩放到最长边上限、转换格式并去除元数据
+///
+/// 不需要任何处理时直接返回原始数据，避免对 JPEG 做无意义的有损重编码。
+/// 重新编码时不保留 EXIF（方向已应用到像素上）；`strip_metadata` 为 false 时保留 ICC 配置。
+pub(crate) fn preprocess_image(bytes: Vec<u8>, config: &PreprocessConfig) -> Result<Vec<u8>, AppError> {
+    if !config.enabled {
+        return Ok(bytes);
+    }
+
+    let reader = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| AppError::validation(format!("读取图片失败：{}", e)))?;
+    let source_format = reader
+        .format()
+        .ok_or_else(|| AppError::validation("无法识别的图片格式"))?;
+    let mut decoder = reader.into_decoder().map_err(preprocess_error)?;
+
+    let orientation = if config.apply_exif_orientation {
+        decoder.orientation().unwrap_or(Orientation::NoTransforms)
+    } else {
+        Orientation::NoTransforms
+    };
+    let has_exif = decoder.exif_metadata().ok().flatten().is_some();
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let (width, height) = decoder.dimensions();
+
+    let target_format = match config.output_format {
+        PreprocessFormat::Keep => source_format,
+        PreprocessFormat::Jpeg => ImageFormat::Jpeg,
+        PreprocessFormat::Png => ImageFormat::Png,
+        PreprocessFormat::Webp => ImageFormat::WebP,
+    };
+    let needs_resize = config.max_edge > 0 && width.max(height) > config.max_edge;
+    let needs_rotate = orientation != Orientation::NoTransforms;
+    let needs_strip = config.strip_metadata && (has_exif || icc_profile.is_some());
+    if !needs_resize && !needs_rotate && target_format == source_format {
+        if !needs_strip {
+            drop(decoder);
+            return Ok(bytes);
+        }
+        // JPEG 只需去除元数据时直接删除元数据段，避免有损重编码改变像素
+        if source_format == ImageFormat::Jpeg {
+            if let Some(stripped) = strip_jpeg_metadata(&bytes, icc_profile.is_some()) {
+                log::debug!("图片预处理：去除 JPEG 元数据（{} 字节 → {} 字节）", bytes.len(), stripped.len());
+                return Ok(stripped);
+            }
+        }
+    }
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(preprocess_error)?;
+    image.apply_orientation(orientation);
+    if needs_resize {
+        image = image.resize(config.max_edge, config.max_edge, FilterType::Triangle);
+    }
+
+    let icc_profile = icc_profile.filter(|_| !config.strip_metadata);
+    let mut output = Vec::new();
+    match target_format {
+        ImageFormat::Jpeg => {
+            let quality = config.jpeg_quality.clamp(1, 100);
+            let encoder = JpegEncoder::new_with_quality(&mut output, quality);
+            encode(encoder, &DynamicImage::ImageRgb8(image.to_rgb8()), icc_profile)
+        }
+        ImageFormat::Png => encode(PngEncoder::new(&mut output), &image, icc_profile),
+        ImageFormat::WebP => encode(WebPEncoder::new_lossless(&mut output), &to_8bit(&image), icc_profile),
+        ImageFormat::Bmp => encode(BmpEncoder::new(&mut output), &to_8bit(&image), icc_profile),
+        other => {
+            return Err(AppError::validation(format!("不支持输出为 {:?} 格式", other)));
+        }
+    }
+    .map_err(preprocess_error)?;
+
+    log::debug!(
+        "图片预处理：{}x{} {:?}（{} 字节）→ {}x{} {:?}（{} 字节）",
+        width,
+        height,
+        source_format,
+        bytes.len(),
+        image.width(),
+        image.height(),
+        target_format,
+        output.len()
+    );
+    Ok(output)
+}
+
+/// 无损去除 JPEG 元数据：删除 APP1（EXIF / XMP）、APP2（ICC、MPF）、APP13（IPTC）和注释段，
+/// 其余段和压缩数据原样保留。段结构无法解析时返回 None（改为重新编码）。
+fn strip_jpeg_metadata(bytes: &[u8], strip_icc: bool) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&bytes[..2]);
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        // SOS 之后为压缩数据，连同后续内容原样复制
+        if marker == 0xDA {
+            output.extend_from_slice(&bytes[pos..]);
+            return Some(output);
+        }
+        let length = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return None;
+        }
+        let is_metadata = matches!(marker, 0xE1 | 0xED | 0xFE) || (marker == 0xE2 && strip_icc);
+        if !is_metadata {
+            output.extend_from_slice(&bytes[pos..end]);
+        }
+        pos = end;
+    }
+}
+
+/// WebP / BMP 编码器只支持 8 位 RGB(A)
+fn to_8bit(image: &DynamicImage) -> DynamicImage {
+    if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    }
+}
+
+fn encode(mut encoder: impl ImageEncoder, image: &DynamicImage, icc_profile: Option<Vec<u8>>) -> ImageResult<()> {
+    if let Some(icc_profile) = icc_profile {
+        if encoder.set_icc_profile(icc_profile).is_err() {
+            log::debug!("输出格式不支持 ICC 配置，已忽略");
+        }
+    }
+    image.write_with_encoder(encoder)
+}
+
+fn preprocess_error(e: image::ImageError) -> AppError {
+    AppError::validation(format!("图片预处理失败：{}", e))
+}
+
+/// 预处理 Base64 编码的图片（可带 data URL 前缀），返回不带前缀的 Base64
+fn preprocess_base64(data: &str, config: &PreprocessConfig) -> Result<String, AppError> {
+    let encoded = match data.split_once(";base64,") {
+        Some((_, encoded)) => encoded,
+        None => data,
+    };
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| AppError::validation(format!("图片数据不是有效的 Base64：{}", e)))?;
+    Ok(BASE64.encode(preprocess_image(bytes, config)?))
+}
+
+/// 在阻塞线程池中执行预处理，避免解码和缩放阻塞异步运行时
+async fn run_blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::validation(format!("图片预处理失败：{}", e)))?
+}
+
+/// 预处理单模态请求中的全部图片
+pub(crate) async fn preprocess_single_request(
+    mut request: SingleModeRequest,
+    config: &PreprocessConfig,
+) -> Result<SingleModeRequest, AppError> {
+    if !config.enabled {
+        return Ok(request);
+    }
+    let config = config.clone();
+    run_blocking(move || {
+        request.images = request
+            .images
+            .iter()
+            .map(|image| preprocess_base64(image, &config))
+            .collect::<Result<_, _>>()?;
+        Ok(request)
+    })
+    .await
+}
+
+/// 预处理融合模式请求中的全部图片对
+pub(crate) async fn preprocess_fusion_request(
+    mut request: FusionModeRequest,
+    config: &PreprocessConfig,
+) -> Result<FusionModeRequest, AppError> {
+    if !config.enabled {
+        return Ok(request);
+    }
+    let config = config.clone();
+    run_blocking(move || {
+        for pair in request.pairs.iter_mut() {
+            pair.rgb = preprocess_base64(&pair.rgb, &config)?;
+            pair.ir = preprocess_base64(&pair.ir, &config)?;
+        }
+        Ok(request)
+    })
+    .await
+}
+
+/// 预处理从磁盘读取的图片内容（用于按路径提交）
+pub(crate) async fn preprocess_file(
+    bytes: Vec<u8>,
+    config: &PreprocessConfig,
+) -> Result<Vec<u8>, AppError> {
+    if !config.enabled {
+        return Ok(bytes);
+    }
+    let config = config.clone();
+    run_blocking(move || preprocess_image(bytes, &config)).await
+}
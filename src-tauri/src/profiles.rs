@@ -0,0 +1,591 @@
+use crate::api::{default_base_url, ApiClient};
+use crate::config::ConfigState;
+use crate::credentials;
+use crate::error::AppError;
+use crate::license::LicenseMonitor;
+use crate::tasks::TaskRegistry;
+use crate::util::HistoryCache;
+use crate::ws::{self, random_below, WsConnectionStateRef};
+use reqwest::{Certificate, Client};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::Connector;
+
+/// 默认请求超时（秒）
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+/// 默认连接超时（秒）
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// TLS 设置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TlsSettings {
+    /// 跳过证书校验（仅用于自签名证书的测试环境）
+    pub accept_invalid_certs: bool,
+    /// 额外信任的 CA 证书（PEM 文件路径）
+    pub ca_cert_path: Option<String>,
+}
+
+/// 后端服务器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendProfile {
+    pub id: String,
+    pub name: String,
+    pub base_url: String,
+    pub request_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    #[serde(default)]
+    pub tls: TlsSettings,
+}
+
+impl BackendProfile {
+    /// 由环境变量生成默认配置（兼容原有的 API_BASE_URL / HTTP_*_TIMEOUT 配置）
+    fn from_env() -> Self {
+        let env_secs = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+
+        BackendProfile {
+            id: "default".to_string(),
+            name: "默认".to_string(),
+            base_url: default_base_url(),
+            request_timeout_secs: env_secs("HTTP_REQUEST_TIMEOUT", DEFAULT_REQUEST_TIMEOUT_SECS),
+            connect_timeout_secs: env_secs("HTTP_CONNECT_TIMEOUT", DEFAULT_CONNECT_TIMEOUT_SECS),
+            tls: TlsSettings::default(),
+        }
+    }
+
+    /// 读取额外信任的 CA 证书
+    fn read_ca_cert(&self) -> Result<Option<Vec<u8>>, AppError> {
+        match self.tls.ca_cert_path {
+            Some(ref path) if !path.trim().is_empty() => fs::read(path)
+                .map(Some)
+                .map_err(|e| AppError::config(format!("读取 CA 证书失败（{}）：{}", path, e))),
+            _ => Ok(None),
+        }
+    }
+
+    /// 按超时和 TLS 设置创建 HTTP 客户端
+    fn http_client(&self) -> Result<Client, AppError> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(self.request_timeout_secs))
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .danger_accept_invalid_certs(self.tls.accept_invalid_certs);
+
+        if let Some(pem) = self.read_ca_cert()? {
+            let cert = Certificate::from_pem(&pem)
+                .map_err(|e| AppError::config(format!("解析 CA 证书失败：{}", e)))?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        builder
+            .build()
+            .map_err(|e| AppError::config(format!("创建 HTTP 客户端失败：{}", e)))
+    }
+
+    /// 按 TLS 设置创建 WebSocket 连接器，使用默认设置时返回 None
+    fn ws_connector(&self) -> Result<Option<Connector>, AppError> {
+        let ca_cert = self.read_ca_cert()?;
+        if !self.tls.accept_invalid_certs && ca_cert.is_none() {
+            return Ok(None);
+        }
+
+        let mut builder = native_tls::TlsConnector::builder();
+        builder.danger_accept_invalid_certs(self.tls.accept_invalid_certs);
+        if let Some(pem) = ca_cert {
+            let cert = native_tls::Certificate::from_pem(&pem)
+                .map_err(|e| AppError::config(format!("解析 CA 证书失败：{}", e)))?;
+            builder.add_root_certificate(cert);
+        }
+
+        let connector = builder
+            .build()
+            .map_err(|e| AppError::config(format!("创建 TLS 连接器失败：{}", e)))?;
+        Ok(Some(Connector::NativeTls(connector)))
+    }
+}
+
+/// 服务器配置列表（持久化到 profiles.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesConfig {
+    pub active_profile_id: String,
+    pub profiles: Vec<BackendProfile>,
+}
+
+impl Default for ProfilesConfig {
+    fn default() -> Self {
+        let profile = BackendProfile::from_env();
+        ProfilesConfig {
+            active_profile_id: profile.id.clone(),
+            profiles: vec![profile],
+        }
+    }
+}
+
+/// 新增或编辑服务器配置的参数（从前端接收）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInput {
+    pub name: String,
+    pub base_url: String,
+    pub request_timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub tls: Option<TlsSettings>,
+}
+
+impl ProfileInput {
+    /// 校验参数并生成配置
+    fn into_profile(self, id: String) -> Result<BackendProfile, AppError> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::validation("配置名称不能为空"));
+        }
+
+        let base_url = self.base_url.trim().trim_end_matches('/').to_string();
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(AppError::validation("服务器地址必须以 http:// 或 https:// 开头"));
+        }
+
+        let request_timeout_secs = self.request_timeout_secs.unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS);
+        let connect_timeout_secs = self.connect_timeout_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+        if request_timeout_secs == 0 || connect_timeout_secs == 0 {
+            return Err(AppError::validation("超时时间必须大于 0"));
+        }
+
+        let profile = BackendProfile {
+            id,
+            name,
+            base_url,
+            request_timeout_secs,
+            connect_timeout_secs,
+            tls: self.tls.unwrap_or_default(),
+        };
+        // 提前检查证书等 TLS 设置是否可用
+        profile.http_client()?;
+        Ok(profile)
+    }
+}
+
+/// 当前服务器对应的连接资源
+struct ActiveConnection {
+    profile: BackendProfile,
+    http: Client,
+    ws_connector: Option<Connector>,
+    /// 从密钥环加载的 API Key（只保存在 Rust 端，不返回给前端）
+    api_key: Option<String>,
+}
+
+impl ActiveConnection {
+    fn new(profile: BackendProfile) -> Result<Self, AppError> {
+        let api_key = credentials::load_profile_api_key(&profile.id).unwrap_or_else(|e| {
+            log::warn!("读取服务器配置 {} 的 API Key 失败：{}", profile.name, e);
+            None
+        });
+        Ok(ActiveConnection {
+            http: profile.http_client()?,
+            ws_connector: profile.ws_connector()?,
+            api_key,
+            profile,
+        })
+    }
+
+    /// 在阻塞线程池中创建连接（读取密钥环和 CA 证书），不占用异步运行时，也不持有配置锁
+    async fn open(profile: BackendProfile) -> Result<Self, AppError> {
+        tokio::task::spawn_blocking(move || ActiveConnection::new(profile))
+            .await
+            .map_err(|e| AppError::config(format!("创建连接失败：{}", e)))?
+    }
+}
+
+struct ProfileStore {
+    config: ProfilesConfig,
+    active: ActiveConnection,
+    auth_errors: UnboundedSender<(String, AppError)>,
+}
+
+impl ProfileStore {
+    fn find(&self, profile_id: &str) -> Result<&BackendProfile, AppError> {
+        self.config
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| AppError::not_found(format!("服务器配置不存在：{}", profile_id)))
+    }
+
+    fn ensure_unique_name(&self, name: &str, except_id: Option<&str>) -> Result<(), AppError> {
+        let duplicated = self
+            .config
+            .profiles
+            .iter()
+            .any(|p| p.name == name && Some(p.id.as_str()) != except_id);
+        if duplicated {
+            return Err(AppError::validation(format!("配置名称已存在：{}", name)));
+        }
+        Ok(())
+    }
+}
+
+/// 服务器配置状态（用于 Tauri State）
+///
+/// 保存所有服务器配置及当前服务器的 HTTP 客户端，所有后端请求都通过
+/// `api_client` 获取指向当前服务器的客户端。
+pub struct ProfileState(Mutex<ProfileStore>);
+
+impl ProfileState {
+    /// 加载服务器配置，当前配置无法使用时回退到环境变量中的默认服务器
+    /// `auth_errors` 用于上报携带 API Key 的请求返回的 401/403
+    pub fn load(auth_errors: UnboundedSender<(String, AppError)>) -> Self {
+        let config = load_profiles_config().unwrap_or_else(|e| {
+            log::warn!("加载服务器配置失败：{}，使用默认配置", e);
+            ProfilesConfig::default()
+        });
+
+        let profile = config
+            .profiles
+            .iter()
+            .find(|p| p.id == config.active_profile_id)
+            .or_else(|| config.profiles.first())
+            .cloned()
+            .unwrap_or_else(BackendProfile::from_env);
+
+        let active = ActiveConnection::new(profile.clone()).unwrap_or_else(|e| {
+            log::error!("服务器配置 {} 无法使用：{}，使用默认设置", profile.name, e);
+            ActiveConnection::new(BackendProfile::from_env()).expect("Failed to create HTTP client")
+        });
+
+        log::info!(
+            "当前服务器：{}（{}），请求超时={}s, 连接超时={}s",
+            active.profile.name,
+            active.profile.base_url,
+            active.profile.request_timeout_secs,
+            active.profile.connect_timeout_secs
+        );
+
+        ProfileState(Mutex::new(ProfileStore {
+            config,
+            active,
+            auth_errors,
+        }))
+    }
+
+    /// 指向当前服务器的 API 客户端（已激活时自动附加 API Key）
+    pub fn api_client(&self) -> ApiClient {
+        let store = self.0.lock().unwrap();
+        let client = ApiClient::new(store.active.http.clone(), store.active.profile.base_url.clone())
+            .with_auth_error_reporter(store.active.profile.id.clone(), store.auth_errors.clone());
+        match store.active.api_key {
+            Some(ref key) => client.with_api_key(key.clone()),
+            None => client,
+        }
+    }
+
+    /// 不附加 API Key 的客户端（激活、健康检查等无需认证的接口）
+    pub fn public_client(&self) -> ApiClient {
+        let store = self.0.lock().unwrap();
+        ApiClient::new(store.active.http.clone(), store.active.profile.base_url.clone())
+    }
+
+    /// 需要认证的接口使用的客户端，当前服务器尚未激活时返回错误
+    pub fn authorized_client(&self) -> Result<ApiClient, AppError> {
+        if !self.has_api_key() {
+            return Err(AppError::unauthorized("当前服务器尚未激活，请先输入激活码"));
+        }
+        Ok(self.api_client())
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        self.0.lock().unwrap().active.api_key.is_some()
+    }
+
+    /// 更新缓存的 API Key（仅当 profile_id 为当前服务器时生效）
+    pub fn set_api_key(&self, profile_id: &str, api_key: Option<String>) {
+        let mut store = self.0.lock().unwrap();
+        if store.active.profile.id == profile_id {
+            store.active.api_key = api_key;
+        }
+    }
+
+    /// 当前服务器的 WebSocket TLS 连接器
+    pub fn ws_connector(&self) -> Option<Connector> {
+        self.0.lock().unwrap().active.ws_connector.clone()
+    }
+
+    pub fn active_profile(&self) -> BackendProfile {
+        self.0.lock().unwrap().active.profile.clone()
+    }
+
+    pub fn get(&self, profile_id: &str) -> Result<BackendProfile, AppError> {
+        self.0.lock().unwrap().find(profile_id).cloned()
+    }
+
+    pub fn list(&self) -> ProfilesConfig {
+        self.0.lock().unwrap().config.clone()
+    }
+
+    fn add(&self, input: ProfileInput) -> Result<BackendProfile, AppError> {
+        let mut store = self.0.lock().unwrap();
+        let profile = input.into_profile(format!("profile-{:08x}", random_below(u32::MAX as u64)))?;
+        store.ensure_unique_name(&profile.name, None)?;
+
+        store.config.profiles.push(profile.clone());
+        save_profiles_config(&store.config)?;
+        Ok(profile)
+    }
+
+    /// 编辑配置，返回编辑后的配置、是否为当前服务器，以及是否因服务器地址变化清除了凭据
+    ///
+    /// 校验配置和创建连接在锁外的阻塞线程中进行，完成后再在锁内替换。
+    /// 凭据按服务器保存：地址变化后旧服务器的 API Key 和授权信息不能发送给新地址，
+    /// 因此删除该配置的密钥环条目，需要重新激活。
+    async fn update(
+        &self,
+        profile_id: &str,
+        input: ProfileInput,
+    ) -> Result<(BackendProfile, bool, bool), AppError> {
+        let previous = self.get(profile_id)?;
+        let id = profile_id.to_string();
+        let (profile, connection, url_changed) = tokio::task::spawn_blocking(move || {
+            let profile = input.into_profile(id)?;
+            let url_changed = profile.base_url != previous.base_url;
+            let mut connection = ActiveConnection::new(profile.clone())?;
+            if url_changed {
+                connection.api_key = None;
+            }
+            Ok::<_, AppError>((profile, connection, url_changed))
+        })
+        .await
+        .map_err(|e| AppError::config(format!("创建连接失败：{}", e)))??;
+
+        let is_active = {
+            let mut store = self.0.lock().unwrap();
+            store.find(profile_id)?;
+            store.ensure_unique_name(&profile.name, Some(profile_id))?;
+            // 以替换时的当前服务器为准（创建连接期间可能已切换）
+            let is_active = store.active.profile.id == profile_id;
+            if is_active {
+                store.active = connection;
+            }
+            if let Some(existing) = store.config.profiles.iter_mut().find(|p| p.id == profile_id) {
+                *existing = profile.clone();
+            }
+            save_profiles_config(&store.config)?;
+            is_active
+        };
+
+        if url_changed {
+            log::info!("服务器配置 {} 的地址已变化，清除其 API Key 和授权信息", profile.name);
+            let id = profile_id.to_string();
+            tokio::task::spawn_blocking(move || credentials::delete_profile_api_key(&id))
+                .await
+                .map_err(|e| AppError::config(format!("清除凭据失败：{}", e)))??;
+        }
+        Ok((profile, is_active, url_changed))
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), AppError> {
+        let mut store = self.0.lock().unwrap();
+        store.find(profile_id)?;
+        if store.active.profile.id == profile_id {
+            return Err(AppError::validation("不能删除当前使用的服务器配置，请先切换到其他配置"));
+        }
+
+        store.config.profiles.retain(|p| p.id != profile_id);
+        save_profiles_config(&store.config)
+    }
+
+    async fn switch(&self, profile_id: &str) -> Result<BackendProfile, AppError> {
+        let profile = self.get(profile_id)?;
+        let connection = ActiveConnection::open(profile.clone()).await?;
+
+        let mut store = self.0.lock().unwrap();
+        store.find(profile_id)?;
+        store.active = connection;
+        store.config.active_profile_id = profile.id.clone();
+        save_profiles_config(&store.config)?;
+        Ok(profile)
+    }
+}
+
+/// 获取服务器配置文件路径
+fn get_profiles_config_path() -> Result<PathBuf, AppError> {
+    let project_path = std::env::var("PROJECT_PATH").map_err(|_| {
+        AppError::config("无法获取 PROJECT_PATH 环境变量，请检查.env 文件是否存在并正确配置")
+    })?;
+
+    let config_path = PathBuf::from(project_path)
+        .join("src-tauri")
+        .join("config")
+        .join("profiles.json");
+
+    Ok(config_path)
+}
+
+/// 加载服务器配置，文件不存在时由环境变量生成默认配置
+fn load_profiles_config() -> Result<ProfilesConfig, AppError> {
+    let config_path = get_profiles_config_path()?;
+
+    if !config_path.exists() {
+        log::info!("服务器配置文件不存在，使用环境变量中的默认服务器：{:?}", config_path);
+        return Ok(ProfilesConfig::default());
+    }
+
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| AppError::config(format!("读取服务器配置文件失败：{}", e)))?;
+
+    let config: ProfilesConfig = serde_json::from_str(&content)
+        .map_err(|e| AppError::config(format!("解析 JSON 失败：{}", e)))?;
+
+    if config.profiles.is_empty() {
+        return Ok(ProfilesConfig::default());
+    }
+    Ok(config)
+}
+
+/// 保存服务器配置
+fn save_profiles_config(config: &ProfilesConfig) -> Result<(), AppError> {
+    let config_path = get_profiles_config_path()?;
+
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::config(format!("创建配置目录失败：{}", e)))?;
+    }
+
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| AppError::config(format!("序列化 JSON 失败：{}", e)))?;
+
+    fs::write(&config_path, content)
+        .map_err(|e| AppError::config(format!("写入服务器配置文件失败：{}", e)))?;
+
+    log::info!("服务器配置已保存：{:?}", config_path);
+    Ok(())
+}
+
+/// 断开当前连接并连接到当前服务器
+/// 当前服务器尚未激活（没有 API Key）时只断开连接并发送 ws_disconnected
+async fn reestablish_connection(
+    app: &AppHandle,
+    ws_state: &WsConnectionStateRef,
+    config: &ConfigState,
+    profiles: &ProfileState,
+) -> Result<(), AppError> {
+    ws::disconnect(ws_state).await;
+
+    match profiles.authorized_client() {
+        Ok(client) => {
+            ws::establish_connection(app, ws_state, config.0.clone(), client, profiles.ws_connector())
+                .await?;
+        }
+        Err(_) => {
+            log::info!("当前服务器尚未激活，不建立 WebSocket 连接");
+            let _ = app.emit("ws_disconnected", ());
+        }
+    }
+    Ok(())
+}
+
+/// 列出所有服务器配置
+#[tauri::command]
+pub fn list_profiles(profiles: State<'_, ProfileState>) -> Result<ProfilesConfig, AppError> {
+    Ok(profiles.list())
+}
+
+/// 新增服务器配置
+#[tauri::command]
+pub fn add_profile(
+    profile: ProfileInput,
+    profiles: State<'_, ProfileState>,
+) -> Result<BackendProfile, AppError> {
+    let profile = profiles.add(profile)?;
+    log::info!("已新增服务器配置：{}（{}）", profile.name, profile.base_url);
+    Ok(profile)
+}
+
+/// 编辑服务器配置，编辑当前服务器时重新建立连接
+///
+/// 服务器地址变化时清除该配置的 API Key 和授权信息，需要重新激活。
+#[tauri::command]
+pub async fn update_profile(
+    app: AppHandle,
+    profile_id: String,
+    profile: ProfileInput,
+    profiles: State<'_, ProfileState>,
+    ws_state: State<'_, WsConnectionStateRef>,
+    config: State<'_, ConfigState>,
+) -> Result<BackendProfile, AppError> {
+    let (profile, is_active, credentials_cleared) = profiles.update(&profile_id, profile).await?;
+    log::info!("已更新服务器配置：{}（{}）", profile.name, profile.base_url);
+    // 地址变化后缓存的历史详情属于旧服务器
+    if credentials_cleared {
+        app.state::<HistoryCache>().invalidate(&profile_id, None);
+    }
+
+    if is_active {
+        let _ = app.emit("profile_changed", &profile);
+        if credentials_cleared {
+            app.state::<LicenseMonitor>().refresh();
+        }
+        reestablish_connection(&app, ws_state.inner(), &config, &profiles).await?;
+    }
+    Ok(profile)
+}
+
+/// 删除服务器配置及其 API Key（不能删除当前服务器）
+#[tauri::command]
+pub fn delete_profile(
+    profile_id: String,
+    profiles: State<'_, ProfileState>,
+    history_cache: State<'_, HistoryCache>,
+) -> Result<(), AppError> {
+    profiles.delete(&profile_id)?;
+    history_cache.invalidate(&profile_id, None);
+    log::info!("已删除服务器配置：{}", profile_id);
+
+    if let Err(e) = credentials::delete_profile_api_key(&profile_id) {
+        log::warn!("删除服务器配置 {} 的 API Key 失败：{}", profile_id, e);
+    }
+    Ok(())
+}
+
+/// 切换当前服务器，断开旧连接并重新建立 WebSocket 连接
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    profile_id: String,
+    profiles: State<'_, ProfileState>,
+    ws_state: State<'_, WsConnectionStateRef>,
+    config: State<'_, ConfigState>,
+) -> Result<BackendProfile, AppError> {
+    let profile = profiles.switch(&profile_id).await?;
+    log::info!("已切换到服务器：{}（{}）", profile.name, profile.base_url);
+
+    let _ = app.emit("profile_changed", &profile);
+    app.state::<LicenseMonitor>().refresh();
+    reestablish_connection(&app, ws_state.inner(), &config, &profiles).await?;
+
+    // 之前在该服务器上提交且未结束的任务不会推送到新连接，改为轮询其状态；
+    // 其他服务器的任务保留在注册表中，不在当前服务器上查询
+    let task_ids = app.state::<TaskRegistry>().active_task_ids(&profile.id);
+    if let (false, Ok(client)) = (task_ids.is_empty(), profiles.authorized_client()) {
+        log::info!("恢复服务器 {} 上 {} 个未完成任务", profile.name, task_ids.len());
+        let recovery = tokio::spawn(ws::recover_in_flight_tasks(
+            app.clone(),
+            client,
+            task_ids,
+            Duration::from_millis(config.0.websocket.task_poll_interval_ms.max(100)),
+        ));
+        if let Some(old) = ws_state.lock().await.recovery_handle.replace(recovery) {
+            old.abort();
+        }
+    }
+    Ok(profile)
+}
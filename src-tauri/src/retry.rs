@@ -0,0 +1,155 @@
+use crate::batch::{self, BatchPayload};
+use crate::config::{ConfigState, RetryConfig};
+use crate::error::AppError;
+use crate::profiles::ProfileState;
+use crate::ws::{self, DetectionResultItem};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 重试任务与原任务的关联
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryLink {
+    /// 最初提交的任务 ID
+    pub original_task_id: String,
+    /// 上一次提交的任务 ID（第一次重试时与 original_task_id 相同）
+    pub parent_task_id: String,
+    /// 第几次重试（从 1 开始）
+    pub attempt: u32,
+    /// 本任务中每张图片（图片对）在原任务中的索引
+    pub image_indices: Vec<u32>,
+}
+
+/// 重试任务提交成功事件（task_retry_submitted）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrySubmittedEvent {
+    /// 新任务 ID，其结果中的 imageIndex 为本任务内的索引，需经 imageIndices 映射回原任务
+    pub task_id: String,
+    #[serde(flatten)]
+    pub link: RetryLink,
+}
+
+/// 提交任务失败后是否可以重试
+///
+/// 提交接口不是幂等的：服务器已收到请求但响应超时或返回 5xx 时重试会创建重复的任务，
+/// 因此只重试确定未被处理的请求——连接失败（错误类别在配置范围内），
+/// 以及状态码在配置范围内的 429 或带有 Retry-After 的响应（如 503）。
+pub(crate) fn is_retryable(config: &RetryConfig, error: &AppError) -> bool {
+    match error.status {
+        Some(status) => {
+            config.retryable_status.contains(&status) && (status == 429 || error.retry_after.is_some())
+        }
+        None => error.not_sent && config.retryable_errors.contains(&error.code),
+    }
+}
+
+/// 计算第 attempt 次重试前的等待时间，后端要求的 Retry-After 更长时以其为准
+pub(crate) fn retry_delay(config: &RetryConfig, attempt: u32, error: &AppError) -> Duration {
+    let delay = ws::backoff(config.initial_delay_ms, config.max_delay_ms, attempt);
+    match error.retry_after {
+        Some(secs) => delay.max(Duration::from_secs(secs)),
+        None => delay,
+    }
+}
+
+/// 本次提交的任务是否还允许重试失败的图片
+fn can_retry_images(app: &AppHandle, link: Option<&RetryLink>) -> bool {
+    let attempt = link.map_or(0, |link| link.attempt);
+    attempt + 1 < app.state::<ConfigState>().0.retry.image_max_attempts
+}
+
+/// 把结果为 error 的图片作为后续任务重新提交，后续任务通过 [`RetryLink`] 关联到原任务
+///
+/// 只用于按路径提交的批量任务：重新提交时按索引取出图片路径，内存中不保留图片数据。
+pub(crate) fn retry_failed_images(
+    app: &AppHandle,
+    task_id: &str,
+    payload: BatchPayload,
+    link: Option<RetryLink>,
+    results: &[DetectionResultItem],
+) {
+    if !can_retry_images(app, link.as_ref()) {
+        return;
+    }
+    let mut failed: Vec<usize> = results
+        .iter()
+        .filter(|r| r.result == "error")
+        .filter_map(|r| r.image_index)
+        .map(|i| i as usize)
+        .filter(|&i| i < payload.len())
+        .collect();
+    failed.sort_unstable();
+    failed.dedup();
+    if failed.is_empty() {
+        return;
+    }
+
+    let link = match link {
+        Some(link) => RetryLink {
+            original_task_id: link.original_task_id,
+            parent_task_id: task_id.to_string(),
+            attempt: link.attempt + 1,
+            image_indices: failed.iter().map(|&i| link.image_indices[i]).collect(),
+        },
+        None => RetryLink {
+            original_task_id: task_id.to_string(),
+            parent_task_id: task_id.to_string(),
+            attempt: 1,
+            image_indices: failed.iter().map(|&i| i as u32).collect(),
+        },
+    };
+    let payload = payload.select(&failed);
+    let config = app.state::<ConfigState>().0.clone();
+    let delay = ws::backoff(config.retry.initial_delay_ms, config.retry.max_delay_ms, link.attempt);
+    log::info!(
+        "任务 {} 中 {} 张图片检测失败，{} 毫秒后第 {} 次重试",
+        task_id,
+        failed.len(),
+        delay.as_millis(),
+        link.attempt
+    );
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let Some(client_id) = batch::current_client_id(&app).await else {
+            log::warn!("重试任务 {} 的失败图片时没有可用的连接，已放弃", link.parent_task_id);
+            return;
+        };
+        let client = match app.state::<ProfileState>().authorized_client() {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("重试任务 {} 的失败图片失败：{}", link.parent_task_id, e);
+                return;
+            }
+        };
+        let parent_task_id = link.parent_task_id.clone();
+        let submitted =
+            batch::submit_linked(&app, &client, &client_id, payload, &config.batch, Some(link)).await;
+        if let Err(e) = submitted {
+            log::error!("重试任务 {} 的失败图片失败：{}", parent_task_id, e);
+        }
+    });
+}
+
+/// 重试任务已创建：发送 task_retry_submitted 事件
+///
+/// 在处理该任务的推送之前调用，前端据此把后续推送中的 imageIndex 映射回原任务。
+/// 任务注册表中的关联在各子任务登记时记录（`retry_of` 为最初提交的任务 ID）。
+pub(crate) fn link_submitted(app: &AppHandle, task_id: &str, link: &RetryLink) {
+    log::info!(
+        "任务 {} 的失败图片已重新提交（第 {} 次重试）：task_id={}",
+        link.original_task_id,
+        link.attempt,
+        task_id
+    );
+    let _ = app.emit(
+        "task_retry_submitted",
+        &RetrySubmittedEvent {
+            task_id: task_id.to_string(),
+            link: link.clone(),
+        },
+    );
+}
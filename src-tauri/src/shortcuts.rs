@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -31,10 +32,10 @@ impl ShortcutConfigState {
 }
 
 /// 获取快捷键配置文件路径
-fn get_shortcuts_config_path() -> Result<PathBuf, String> {
+fn get_shortcuts_config_path() -> Result<PathBuf, AppError> {
     // 从环境变量获取项目路径
     let project_path = std::env::var("PROJECT_PATH").map_err(|_| {
-        "无法获取 PROJECT_PATH 环境变量，请检查.env 文件是否存在并正确配置".to_string()
+        AppError::config("无法获取 PROJECT_PATH 环境变量，请检查.env 文件是否存在并正确配置")
     })?;
 
     let config_path = PathBuf::from(project_path)
@@ -46,7 +47,7 @@ fn get_shortcuts_config_path() -> Result<PathBuf, String> {
 }
 
 /// 加载快捷键配置
-pub fn load_shortcuts_config() -> Result<ShortcutConfig, String> {
+pub fn load_shortcuts_config() -> Result<ShortcutConfig, AppError> {
     let config_path = get_shortcuts_config_path()?;
 
     if !config_path.exists() {
@@ -55,30 +56,30 @@ pub fn load_shortcuts_config() -> Result<ShortcutConfig, String> {
     }
 
     let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("读取快捷键配置文件失败：{}", e))?;
+        .map_err(|e| AppError::config(format!("读取快捷键配置文件失败：{}", e)))?;
 
     let config: ShortcutConfig = serde_json::from_str(&content)
-        .map_err(|e| format!("解析 JSON 失败：{}", e))?;
+        .map_err(|e| AppError::config(format!("解析 JSON 失败：{}", e)))?;
 
     log::info!("快捷键配置加载成功：{:?}", config);
     Ok(config)
 }
 
 /// 保存快捷键配置
-pub fn save_shortcuts_config(config: &ShortcutConfig) -> Result<(), String> {
+pub fn save_shortcuts_config(config: &ShortcutConfig) -> Result<(), AppError> {
     let config_path = get_shortcuts_config_path()?;
 
     // 确保目录存在
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("创建配置目录失败：{}", e))?;
+            .map_err(|e| AppError::config(format!("创建配置目录失败：{}", e)))?;
     }
 
     let content = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("序列化 JSON 失败：{}", e))?;
+        .map_err(|e| AppError::config(format!("序列化 JSON 失败：{}", e)))?;
 
     fs::write(&config_path, content)
-        .map_err(|e| format!("写入配置文件失败：{}", e))?;
+        .map_err(|e| AppError::config(format!("写入配置文件失败：{}", e)))?;
 
     log::info!("快捷键配置已保存：{:?}", config_path);
     Ok(())
@@ -90,7 +91,7 @@ fn is_allowed_standalone_key(key: &str) -> bool {
 }
 
 /// 验证快捷键格式是否有效
-pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
+pub fn validate_shortcut(shortcut: &str) -> Result<(), AppError> {
     log::info!("validate_shortcut 收到：'{}' (len={})", shortcut, shortcut.len());
 
     // 逐字符打印
@@ -99,7 +100,7 @@ pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
     }
 
     if shortcut.trim().is_empty() {
-        return Err("快捷键不能为空".to_string());
+        return Err(AppError::validation("快捷键不能为空"));
     }
 
     // 检查是否是允许单独使用的特殊键
@@ -121,20 +122,20 @@ pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
     log::info!("validate_shortcut contains('Meta'): {}", shortcut.contains("Meta"));
 
     if !has_modifier {
-        return Err("快捷键必须包含至少一个修饰键（Ctrl/Alt/Shift/Meta），或使用特殊键（Escape/Enter）".to_string());
+        return Err(AppError::validation("快捷键必须包含至少一个修饰键（Ctrl/Alt/Shift/Meta），或使用特殊键（Escape/Enter）"));
     }
 
     // 检查是否包含主键
     let keys: Vec<&str> = shortcut.split('+').collect();
     if keys.is_empty() {
-        return Err("快捷键格式无效".to_string());
+        return Err(AppError::validation("快捷键格式无效"));
     }
 
     let main_key = keys.last().unwrap_or(&"");
     let modifiers = ["CTRL", "ALT", "SHIFT", "META"];
 
     if modifiers.contains(&main_key.to_uppercase().as_str()) {
-        return Err("快捷键必须包含一个主键".to_string());
+        return Err(AppError::validation("快捷键必须包含一个主键"));
     }
 
     Ok(())
@@ -142,7 +143,7 @@ pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
 
 /// Tauri 命令：加载快捷键配置
 #[tauri::command]
-pub fn get_shortcuts_config(state: tauri::State<ShortcutConfigState>) -> Result<ShortcutConfig, String> {
+pub fn get_shortcuts_config(state: tauri::State<ShortcutConfigState>) -> Result<ShortcutConfig, AppError> {
     let config = state.0.blocking_lock();
     Ok(config.clone())
 }
@@ -152,7 +153,7 @@ pub fn get_shortcuts_config(state: tauri::State<ShortcutConfigState>) -> Result<
 pub fn save_shortcuts_config_command(
     config: ShortcutConfig,
     state: tauri::State<ShortcutConfigState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     log::info!("save_shortcuts_config_command 收到配置：start_detection='{}', cancel_task='{}', reset='{}'",
                config.start_detection, config.cancel_task, config.reset);
 
@@ -167,13 +168,13 @@ pub fn save_shortcuts_config_command(
     // 检查是否有重复的快捷键
     let mut seen = std::collections::HashSet::new();
     if !seen.insert(&config.start_detection) {
-        return Err("快捷键冲突：开始检测的快捷键重复".to_string());
+        return Err(AppError::validation("快捷键冲突：开始检测的快捷键重复"));
     }
     if !seen.insert(&config.cancel_task) {
-        return Err("快捷键冲突：取消任务的快捷键重复".to_string());
+        return Err(AppError::validation("快捷键冲突：取消任务的快捷键重复"));
     }
     if !seen.insert(&config.reset) {
-        return Err("快捷键冲突：清空重置的快捷键重复".to_string());
+        return Err(AppError::validation("快捷键冲突：清空重置的快捷键重复"));
     }
 
     // 保存到文件
@@ -0,0 +1,419 @@
+use crate::api::{
+    ApiClient, BackendImageUploadRequest, BackendStoredImage, BackendStoredImageListResponse,
+    StoredImageListParams,
+};
+use crate::config::ConfigState;
+use crate::error::AppError;
+use crate::profiles::ProfileState;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+
+/// 按任务查询图片时的每页数量
+const TASK_IMAGES_PAGE_SIZE: u32 = 1000;
+
+/// 图片上传请求（前端传入）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUploadRequest {
+    /// Base64 编码的图片数据（可带 data URL 前缀）
+    pub image_data: String,
+    pub filename: Option<String>,
+    pub task_id: Option<String>,
+    pub image_index: Option<u32>,
+    pub modality: Option<String>,
+}
+
+/// 图片内容（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredImageData {
+    pub image_id: String,
+    pub content_type: String,
+    pub size: u64,
+    /// 可直接用于 <img src> 的 data URL
+    pub data_url: String,
+    /// 是否命中本地缓存
+    pub from_cache: bool,
+}
+
+// ===== 本地缓存 =====
+
+fn content_type_for(extension: &str) -> &'static str {
+    match extension {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or("").trim() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/bmp" => "bmp",
+        "image/webp" => "webp",
+        _ => "bin",
+    }
+}
+
+/// 图片 ID 用作文件名前去掉路径分隔符等字符
+fn cache_key(image_id: &str) -> String {
+    image_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// 缓存根目录（按服务器配置区分，不同后端的图片 ID 可能重复）
+fn cache_root(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join("images"))
+        .map_err(|e| AppError::config(format!("无法获取缓存目录：{}", e)))
+}
+
+fn cache_dir(app: &AppHandle, profile_id: &str) -> Result<PathBuf, AppError> {
+    Ok(cache_root(app)?.join(cache_key(profile_id)))
+}
+
+/// 缓存文件可能使用的扩展名（与 extension_for 的返回值一致）
+const CACHE_EXTENSIONS: [&str; 5] = ["jpg", "png", "bmp", "webp", "bin"];
+
+/// 超出上限时清理到上限的该百分比，避免之后每次写入都再次清理
+const EVICT_TARGET_PERCENT: u64 = 90;
+
+/// 图片缓存占用的磁盘空间
+///
+/// 首次写入缓存时统计一次，之后随写入和删除更新，超出上限时才扫描缓存目录进行清理。
+#[derive(Default)]
+pub struct ImageCache(Mutex<Option<u64>>);
+
+impl ImageCache {
+    /// 记录新写入的缓存文件，返回是否需要扫描缓存目录（尚未统计或超出上限）
+    fn add(&self, size: u64, max_bytes: u64) -> bool {
+        match self.0.lock().unwrap().as_mut() {
+            Some(total) => {
+                *total += size;
+                *total > max_bytes
+            }
+            None => true,
+        }
+    }
+
+    fn set(&self, total: u64) {
+        *self.0.lock().unwrap() = Some(total);
+    }
+
+    fn remove(&self, size: u64) {
+        if let Some(total) = self.0.lock().unwrap().as_mut() {
+            *total = total.saturating_sub(size);
+        }
+    }
+}
+
+/// 查找已缓存的图片文件（按已知扩展名直接拼接路径）
+fn find_cached(dir: &Path, image_id: &str) -> Option<PathBuf> {
+    let key = cache_key(image_id);
+    CACHE_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", key, extension)))
+        .find(|path| path.is_file())
+}
+
+/// 读取已缓存的图片，返回内容和 Content-Type
+fn read_cached(dir: &Path, image_id: &str) -> Option<(Vec<u8>, &'static str)> {
+    let path = find_cached(dir, image_id)?;
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            // 更新修改时间，作为缓存淘汰的访问时间
+            if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            Some((bytes, content_type_for(extension)))
+        }
+        Err(e) => {
+            log::warn!("读取图片缓存失败：{} - {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// 删除已缓存的图片，返回释放的字节数
+fn remove_cached(dir: &Path, image_id: &str) -> u64 {
+    let Some(path) = find_cached(dir, image_id) else {
+        return 0;
+    };
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    match std::fs::remove_file(&path) {
+        Ok(()) => size,
+        Err(e) => {
+            log::warn!("删除图片缓存失败：{} - {}", path.display(), e);
+            0
+        }
+    }
+}
+
+/// 统计缓存占用，超出上限时按最后访问时间删除最早的文件，返回清理后的占用
+fn evict_cache(root: &Path, max_bytes: u64) -> u64 {
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.ok())
+        .filter_map(|dir| std::fs::read_dir(dir.path()).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            metadata
+                .is_file()
+                .then(|| (entry.path(), metadata.len(), metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return total;
+    }
+    let target = max_bytes / 100 * EVICT_TARGET_PERCENT;
+    files.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in files {
+        if total <= target {
+            break;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => total -= size,
+            Err(e) => log::warn!("清理图片缓存失败：{} - {}", path.display(), e),
+        }
+    }
+    total
+}
+
+/// 写入图片缓存，必要时清理
+fn write_cached(
+    app: &AppHandle,
+    dir: &Path,
+    image_id: &str,
+    extension: &str,
+    bytes: &[u8],
+    max_bytes: u64,
+) {
+    let write_result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(dir.join(format!("{}.{}", cache_key(image_id), extension)), bytes));
+    if let Err(e) = write_result {
+        log::warn!("写入图片缓存失败：{}", e);
+        return;
+    }
+    let cache = app.state::<ImageCache>();
+    if cache.add(bytes.len() as u64, max_bytes) {
+        if let Ok(root) = cache_root(app) {
+            cache.set(evict_cache(&root, max_bytes));
+        }
+    }
+}
+
+fn image_data(image_id: &str, bytes: &[u8], content_type: &str, from_cache: bool) -> StoredImageData {
+    StoredImageData {
+        image_id: image_id.to_string(),
+        content_type: content_type.to_string(),
+        size: bytes.len() as u64,
+        data_url: format!("data:{};base64,{}", content_type, BASE64.encode(bytes)),
+        from_cache,
+    }
+}
+
+/// 读取图片：优先使用本地缓存，未命中时从后端下载并写入缓存
+async fn load_image(
+    app: &AppHandle,
+    profiles: &ProfileState,
+    config: &ConfigState,
+    image_id: &str,
+) -> Result<StoredImageData, AppError> {
+    let client = profiles.authorized_client()?;
+    let dir = cache_dir(app, &profiles.active_profile().id)?;
+
+    let cached = {
+        let dir = dir.clone();
+        let image_id = image_id.to_string();
+        tokio::task::spawn_blocking(move || read_cached(&dir, &image_id))
+            .await
+            .ok()
+            .flatten()
+    };
+    if let Some((bytes, content_type)) = cached {
+        log::debug!("图片 {} 命中本地缓存", image_id);
+        return Ok(image_data(image_id, &bytes, content_type, true));
+    }
+
+    let (bytes, content_type) = client.fetch_image(image_id).await?;
+    let extension = content_type.as_deref().map(extension_for).unwrap_or("bin");
+    let data = image_data(image_id, &bytes, content_type_for(extension), false);
+
+    let app = app.clone();
+    let image_id = image_id.to_string();
+    let max_bytes = config.0.storage.cache_max_mb * 1024 * 1024;
+    tauri::async_runtime::spawn_blocking(move || {
+        write_cached(&app, &dir, &image_id, extension, &bytes, max_bytes)
+    });
+
+    Ok(data)
+}
+
+// ===== 命令 =====
+
+/// 上传图片到后端存储
+#[tauri::command]
+pub async fn upload_image(
+    request: ImageUploadRequest,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<BackendStoredImage, AppError> {
+    let encoded = match request.image_data.split_once(";base64,") {
+        Some((_, data)) => data,
+        None => request.image_data.as_str(),
+    };
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| AppError::validation(format!("图片数据不是有效的 Base64：{}", e)))?;
+    if bytes.is_empty() {
+        return Err(AppError::validation("图片数据为空"));
+    }
+    let max_bytes = config.0.image.max_file_size_mb * 1024 * 1024;
+    if bytes.len() as u64 > max_bytes {
+        return Err(AppError::validation(format!(
+            "图片大小超过限制：{:.1} MB（上限 {} MB）",
+            bytes.len() as f64 / 1024.0 / 1024.0,
+            config.0.image.max_file_size_mb
+        )));
+    }
+
+    let client = profiles.authorized_client()?;
+    let stored = client
+        .upload_image(&BackendImageUploadRequest {
+            image: BASE64.encode(&bytes),
+            filename: request.filename,
+            task_id: request.task_id,
+            image_index: request.image_index,
+            modality: request.modality,
+        })
+        .await?;
+
+    log::info!("图片上传成功：image_id={}", stored.image_id);
+    Ok(stored)
+}
+
+/// 查询已存储的图片
+#[tauri::command]
+pub async fn list_stored_images(
+    params: Option<StoredImageListParams>,
+    profiles: State<'_, ProfileState>,
+) -> Result<BackendStoredImageListResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    client.list_images(&params.unwrap_or_default()).await
+}
+
+/// 获取图片内容（使用本地磁盘缓存）
+#[tauri::command]
+pub async fn fetch_stored_image(
+    app: AppHandle,
+    image_id: String,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<StoredImageData, AppError> {
+    load_image(&app, &profiles, &config, &image_id).await
+}
+
+/// 删除已存储的图片（同时删除本地缓存）
+#[tauri::command]
+pub async fn delete_stored_image(
+    app: AppHandle,
+    image_id: String,
+    profiles: State<'_, ProfileState>,
+) -> Result<(), AppError> {
+    let client = profiles.authorized_client()?;
+    client.delete_image(&image_id).await?;
+    let dir = cache_dir(&app, &profiles.active_profile().id)?;
+    let removed = {
+        let image_id = image_id.clone();
+        tokio::task::spawn_blocking(move || remove_cached(&dir, &image_id))
+            .await
+            .unwrap_or(0)
+    };
+    app.state::<ImageCache>().remove(removed);
+
+    log::info!("已删除存储的图片：{}", image_id);
+    Ok(())
+}
+
+// ===== 任务图片关联 =====
+
+/// 查询任务的所有已存储图片（逐页获取），按 image_index 和模态排序
+async fn task_images(client: &ApiClient, task_id: &str) -> Result<Vec<BackendStoredImage>, AppError> {
+    let mut images = Vec::new();
+    for page in 1.. {
+        let response = client
+            .list_images(&StoredImageListParams {
+                task_id: Some(task_id.to_string()),
+                page: Some(page),
+                page_size: Some(TASK_IMAGES_PAGE_SIZE),
+            })
+            .await?;
+        let count = response.items.len();
+        images.extend(response.items);
+        if count < TASK_IMAGES_PAGE_SIZE as usize || images.len() >= response.total as usize {
+            break;
+        }
+    }
+    // 后端未按 task_id 过滤时在本地过滤
+    images.retain(|image| image.task_id.as_deref() == Some(task_id));
+    images.sort_by(|a, b| {
+        (a.image_index, a.modality.as_deref()).cmp(&(b.image_index, b.modality.as_deref()))
+    });
+    Ok(images)
+}
+
+/// 获取任务的所有已存储图片
+///
+/// 返回的 image_index 与历史记录 HistoryResultItem.image_index 对应。
+#[tauri::command]
+pub async fn get_task_images(
+    task_id: String,
+    profiles: State<'_, ProfileState>,
+) -> Result<Vec<BackendStoredImage>, AppError> {
+    task_images(&profiles.authorized_client()?, &task_id).await
+}
+
+/// 根据历史结果的 image_index（融合模式下还需模态）获取对应的图片内容
+#[tauri::command]
+pub async fn fetch_task_image(
+    app: AppHandle,
+    task_id: String,
+    image_index: u32,
+    modality: Option<String>,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<StoredImageData, AppError> {
+    let images = task_images(&profiles.authorized_client()?, &task_id).await?;
+    let image = images
+        .into_iter()
+        .filter(|image| image.image_index == Some(image_index))
+        .find(|image| match modality {
+            Some(ref modality) => image.modality.as_deref() == Some(modality.as_str()),
+            None => true,
+        })
+        .ok_or_else(|| {
+            AppError::not_found(format!(
+                "任务 {} 没有第 {} 张图片的存储记录",
+                task_id, image_index
+            ))
+        })?;
+
+    load_image(&app, &profiles, &config, &image.image_id).await
+}
@@ -0,0 +1,309 @@
+use crate::error::AppError;
+use crate::ws::DetectionResultItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// 保留的已结束任务数量上限（超出后淘汰最早结束的任务）
+const MAX_FINISHED_TASKS: usize = 200;
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Completed,
+    PartialFailure,
+    Failed,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// 解析后端返回的状态字符串，未知状态视为运行中
+    pub fn from_backend(status: &str) -> Self {
+        match status {
+            "pending" | "queued" => TaskStatus::Pending,
+            "completed" => TaskStatus::Completed,
+            "partial_failure" => TaskStatus::PartialFailure,
+            "failed" => TaskStatus::Failed,
+            "cancelled" => TaskStatus::Cancelled,
+            _ => TaskStatus::Running,
+        }
+    }
+
+    /// 与后端一致的状态字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "pending",
+            TaskStatus::Running => "running",
+            TaskStatus::Completed => "completed",
+            TaskStatus::PartialFailure => "partial_failure",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Completed | TaskStatus::PartialFailure | TaskStatus::Failed | TaskStatus::Cancelled
+        )
+    }
+}
+
+/// 任务记录（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRecord {
+    pub task_id: String,
+    /// "single" 或 "fusion"，任务提交前收到推送时为空
+    pub mode: String,
+    /// 提交任务的服务器配置，任务提交前收到推送时为空
+    pub profile_id: String,
+    pub modality: Option<String>,
+    /// 提交时间（Unix 毫秒）
+    pub submitted_at: u64,
+    pub total_items: u32,
+    pub processed_items: u32,
+    pub status: TaskStatus,
+    pub message: Option<String>,
+    /// 已收到的单张结果
+    pub results: Vec<DetectionResultItem>,
+    /// 结束时间（Unix 毫秒）
+    pub finished_at: Option<u64>,
+    /// 重试失败图片的后续任务：最初提交的任务 ID
+    pub retry_of: Option<String>,
+    /// 重试次数（原任务为 0）
+    pub retry_attempt: u32,
+}
+
+impl TaskRecord {
+    fn new(task_id: &str) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            mode: String::new(),
+            profile_id: String::new(),
+            modality: None,
+            submitted_at: now_millis(),
+            total_items: 0,
+            processed_items: 0,
+            status: TaskStatus::Pending,
+            message: None,
+            results: Vec::new(),
+            finished_at: None,
+            retry_of: None,
+            retry_attempt: 0,
+        }
+    }
+
+    fn has_result(&self, image_index: u32) -> bool {
+        self.results.iter().any(|r| r.image_index == Some(image_index))
+    }
+}
+
+/// 任务注册表
+///
+/// 记录本客户端提交的所有检测任务及其进度，由检测命令和 WebSocket 消息共同维护，
+/// 前端刷新或新窗口可据此恢复当前状态；断线重连后也据此找出需要补发结果的任务。
+#[derive(Default)]
+pub struct TaskRegistry(Mutex<HashMap<String, TaskRecord>>);
+
+impl TaskRegistry {
+    /// 登记新提交的任务（任务推送先于提交响应到达时保留已有进度）
+    pub fn register(
+        &self,
+        task_id: &str,
+        profile_id: &str,
+        mode: &str,
+        modality: Option<&str>,
+        total_items: u32,
+    ) {
+        let mut tasks = self.0.lock().unwrap();
+        let record = tasks
+            .entry(task_id.to_string())
+            .or_insert_with(|| TaskRecord::new(task_id));
+        record.profile_id = profile_id.to_string();
+        record.mode = mode.to_string();
+        record.modality = modality.map(|m| m.to_string());
+        if record.total_items == 0 {
+            record.total_items = total_items;
+        }
+    }
+
+    /// 更新任务进度并记录单张结果，返回该结果是否首次出现（已结束的任务返回 false）
+    pub fn record_progress(
+        &self,
+        task_id: &str,
+        total_items: Option<u32>,
+        processed_items: Option<u32>,
+        message: Option<&str>,
+        result: Option<&DetectionResultItem>,
+    ) -> bool {
+        let mut tasks = self.0.lock().unwrap();
+        let record = tasks
+            .entry(task_id.to_string())
+            .or_insert_with(|| TaskRecord::new(task_id));
+        if record.status.is_terminal() {
+            return false;
+        }
+
+        record.status = TaskStatus::Running;
+        if let Some(total) = total_items {
+            record.total_items = total;
+        }
+        if let Some(processed) = processed_items {
+            record.processed_items = record.processed_items.max(processed);
+        }
+        if let Some(msg) = message {
+            record.message = Some(msg.to_string());
+        }
+
+        match result {
+            Some(r) => match r.image_index {
+                Some(index) if record.has_result(index) => false,
+                _ => {
+                    record.results.push(r.clone());
+                    true
+                }
+            },
+            None => true,
+        }
+    }
+
+    /// 标记任务结束，返回是否首次结束
+    pub fn finish(
+        &self,
+        task_id: &str,
+        status: TaskStatus,
+        message: Option<&str>,
+        processed_items: Option<u32>,
+    ) -> bool {
+        let mut tasks = self.0.lock().unwrap();
+        let record = tasks
+            .entry(task_id.to_string())
+            .or_insert_with(|| TaskRecord::new(task_id));
+        if record.status.is_terminal() {
+            return false;
+        }
+
+        record.status = status;
+        record.finished_at = Some(now_millis());
+        if let Some(msg) = message {
+            record.message = Some(msg.to_string());
+        }
+        if let Some(processed) = processed_items {
+            record.processed_items = record.processed_items.max(processed);
+        }
+
+        evict_finished(&mut tasks);
+        true
+    }
+
+    /// 记录重试任务与原任务的关联
+    pub fn link_retry(&self, task_id: &str, original_task_id: &str, attempt: u32) {
+        if let Some(record) = self.0.lock().unwrap().get_mut(task_id) {
+            record.retry_of = Some(original_task_id.to_string());
+            record.retry_attempt = attempt;
+        }
+    }
+
+    /// 指定服务器配置下未结束任务的 ID（其他服务器的任务不能在当前服务器上查询）
+    pub fn active_task_ids(&self, profile_id: &str) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|r| !r.status.is_terminal() && r.profile_id == profile_id)
+            .map(|r| r.task_id.clone())
+            .collect()
+    }
+
+    /// 任务是否已由提交响应登记（只收到推送、尚未登记的记录 profile_id 为空）
+    pub fn is_registered(&self, task_id: &str) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .is_some_and(|r| !r.profile_id.is_empty())
+    }
+
+    pub fn is_active(&self, task_id: &str) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .is_some_and(|r| !r.status.is_terminal())
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<TaskRecord> {
+        self.0.lock().unwrap().get(task_id).cloned()
+    }
+
+    /// 所有任务，按提交时间倒序
+    pub fn list(&self) -> Vec<TaskRecord> {
+        let mut records: Vec<TaskRecord> = self.0.lock().unwrap().values().cloned().collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.submitted_at));
+        records
+    }
+
+    /// 清除任务记录，默认只清除已结束的任务，返回清除数量
+    pub fn clear(&self, include_active: bool) -> usize {
+        let mut tasks = self.0.lock().unwrap();
+        let before = tasks.len();
+        tasks.retain(|_, r| !include_active && !r.status.is_terminal());
+        before - tasks.len()
+    }
+}
+
+/// 已结束任务超出上限时淘汰最早结束的记录
+fn evict_finished(tasks: &mut HashMap<String, TaskRecord>) {
+    let mut finished: Vec<(u64, String)> = tasks
+        .values()
+        .filter_map(|r| r.finished_at.map(|t| (t, r.task_id.clone())))
+        .collect();
+    if finished.len() <= MAX_FINISHED_TASKS {
+        return;
+    }
+    finished.sort();
+    for (_, task_id) in finished.iter().take(finished.len() - MAX_FINISHED_TASKS) {
+        tasks.remove(task_id);
+    }
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 列出本客户端提交的所有任务
+#[tauri::command]
+pub fn list_tasks(registry: State<'_, TaskRegistry>) -> Result<Vec<TaskRecord>, AppError> {
+    Ok(registry.list())
+}
+
+/// 获取单个任务记录
+#[tauri::command]
+pub fn get_task(
+    task_id: String,
+    registry: State<'_, TaskRegistry>,
+) -> Result<TaskRecord, AppError> {
+    registry
+        .get(&task_id)
+        .ok_or_else(|| AppError::not_found(format!("任务不存在：{}", task_id)))
+}
+
+/// 清除任务记录（默认只清除已结束的任务）
+#[tauri::command]
+pub fn clear_tasks(
+    include_active: Option<bool>,
+    registry: State<'_, TaskRegistry>,
+) -> Result<usize, AppError> {
+    let removed = registry.clear(include_active.unwrap_or(false));
+    log::info!("已清除 {} 条任务记录", removed);
+    Ok(removed)
+}
@@ -0,0 +1,192 @@
+use crate::api::{ApiClient, BackendTaskStatusResponse};
+use crate::batch;
+use crate::error::{AppError, ErrorCode};
+use crate::profiles::ProfileState;
+use crate::tasks::{TaskRegistry, TaskStatus};
+use crate::ws::{random_below, DetectionResultItem, WsConnectionStateRef, WsEventMessage};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 当前使用的任务推送方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportMode {
+    /// 通过 WebSocket 接收后端推送
+    #[default]
+    Websocket,
+    /// 通过 HTTP 轮询任务状态（后端推送不可用时）
+    Polling,
+}
+
+/// 推送方式切换事件（transport_changed）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransportChangedEvent {
+    pub mode: TransportMode,
+    /// 切换原因（如 WebSocket 连接失败的错误信息）
+    pub reason: Option<String>,
+}
+
+/// 切换到 HTTP 轮询模式
+///
+/// 生成本地 client_id 供提交任务使用，之后定时轮询注册表中所有未结束任务的状态，
+/// 并发出与 WebSocket 推送相同的 ws_progress / ws_task_completed / ws_task_failed 事件。
+/// 调用方需先停止旧的连接任务。
+pub(crate) async fn start_polling_transport(
+    app: &AppHandle,
+    ws_state: &WsConnectionStateRef,
+    client: ApiClient,
+    poll_interval_ms: u64,
+    reason: Option<String>,
+) -> String {
+    let client_id = format!("poll-{:016x}", random_below(u64::MAX));
+    let poll_interval = Duration::from_millis(poll_interval_ms.max(100));
+    let poller = tokio::spawn(poll_active_tasks(app.clone(), client, poll_interval));
+
+    {
+        let mut state = ws_state.lock().await;
+        if let Some(old) = state.abort_handle.replace(poller) {
+            old.abort();
+        }
+        state.client_id = Some(client_id.clone());
+        state.is_connected = true;
+        state.transport = TransportMode::Polling;
+        state.reconnect_attempts = 0;
+        state.last_error = reason.clone();
+    }
+
+    log::warn!(
+        "已切换到 HTTP 轮询模式，client_id: {}，原因：{}",
+        client_id,
+        reason.as_deref().unwrap_or("配置指定")
+    );
+    let _ = app.emit("ws_connected", &client_id);
+    let _ = app.emit(
+        "transport_changed",
+        &TransportChangedEvent {
+            mode: TransportMode::Polling,
+            reason,
+        },
+    );
+    client_id
+}
+
+/// 轮询循环：每个周期查询一次所有未结束任务的状态
+async fn poll_active_tasks(app: AppHandle, client: ApiClient, poll_interval: Duration) {
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+        let profile_id = app.state::<ProfileState>().active_profile().id;
+        let task_ids = app.state::<TaskRegistry>().active_task_ids(&profile_id);
+        for task_id in &task_ids {
+            let _ = poll_task(&app, &client, task_id).await;
+        }
+    }
+}
+
+/// 查询单个任务状态并补发对应事件
+///
+/// 后端不存在该任务时标记为失败；其他查询错误返回给调用方，由调用方决定是否继续轮询。
+pub(crate) async fn poll_task(app: &AppHandle, client: &ApiClient, task_id: &str) -> Result<(), AppError> {
+    match client.task_status(task_id).await {
+        Ok(status) => {
+            apply_task_status(app, &status);
+            Ok(())
+        }
+        Err(e) if e.code == ErrorCode::NotFound => {
+            log::warn!("任务 {} 在后端不存在，标记为失败", task_id);
+            fail_task(app, task_id, "任务不存在或已过期");
+            Ok(())
+        }
+        Err(e) => {
+            log::warn!("查询任务 {} 状态失败：{}", task_id, e);
+            Err(e)
+        }
+    }
+}
+
+/// 把任务标记为失败并发送 ws_task_failed 事件（已结束的任务忽略）
+pub(crate) fn fail_task(app: &AppHandle, task_id: &str, message: &str) {
+    let registry = app.state::<TaskRegistry>();
+    if registry.finish(task_id, TaskStatus::Failed, Some(message), None) {
+        batch::emit_task_event(
+            app,
+            "ws_task_failed",
+            WsEventMessage {
+                event_type: "task_failed".to_string(),
+                task_id: task_id.to_string(),
+                status: Some("failed".to_string()),
+                message: Some(message.to_string()),
+                result: None,
+                total_items: None,
+                processed_items: None,
+                completed_results: None,
+            },
+        );
+    }
+}
+
+/// 根据轮询到的任务状态补发 ws_progress / ws_task_completed / ws_task_failed 事件
+pub(crate) fn apply_task_status(app: &AppHandle, status: &BackendTaskStatusResponse) {
+    let registry = app.state::<TaskRegistry>();
+    let task_id = &status.task_id;
+
+    // 先补发尚未推送的单张结果，前端据此累积结果列表
+    registry.record_progress(
+        task_id,
+        Some(status.total_items),
+        Some(status.completed_items),
+        status.message.as_deref(),
+        None,
+    );
+    for item in &status.results {
+        let result: DetectionResultItem = item.clone().into();
+        if !registry.record_progress(task_id, None, None, None, Some(&result)) {
+            continue;
+        }
+        batch::emit_task_event(
+            app,
+            "ws_progress",
+            WsEventMessage {
+                event_type: "progress".to_string(),
+                task_id: task_id.clone(),
+                status: Some("running".to_string()),
+                message: status.message.clone(),
+                result: Some(result),
+                total_items: Some(status.total_items),
+                processed_items: Some(status.completed_items),
+                completed_results: None,
+            },
+        );
+    }
+
+    if !status.is_terminal() {
+        return;
+    }
+    let terminal = TaskStatus::from_backend(&status.status);
+    if !registry.finish(task_id, terminal, status.message.as_deref(), Some(status.completed_items)) {
+        return;
+    }
+
+    let (event_name, event_type) = match status.status.as_str() {
+        "failed" => ("ws_task_failed", "task_failed"),
+        _ => ("ws_task_completed", "task_completed"),
+    };
+    log::info!("补发任务 {} 的结束事件：{}", task_id, status.status);
+    batch::emit_task_event(
+        app,
+        event_name,
+        WsEventMessage {
+            event_type: event_type.to_string(),
+            task_id: task_id.clone(),
+            status: Some(status.status.clone()),
+            message: status.message.clone(),
+            result: None,
+            total_items: Some(status.total_items),
+            processed_items: Some(status.completed_items),
+            completed_results: Some(status.results.iter().cloned().map(Into::into).collect()),
+        },
+    );
+}
+
@@ -1,811 +1,474 @@
+use crate::api::{
+    AsyncTaskResponse, BackendHistoryResultItem, BackendHistoryStatsResponse,
+    BackendHistoryTaskItem, BackendTaskListResponse, BackendTaskStatusResponse,
+    BackendTaskSummary, FusionModeRequest, HistoryDeleteParams, HistoryQueryParams,
+    HistoryStatsParams, SingleModeRequest,
+};
+use crate::activation::{ActivationCode, ActivationCodeError};
+use crate::batch::{self, BatchPayload};
 use crate::config::ConfigState;
-use futures_util::{SinkExt, StreamExt};
-use reqwest::Client;
+use crate::credentials;
+use crate::error::AppError;
+use crate::image_files::{
+    self, FolderDetectRequest, FolderScanResult, FusionModePathRequest, ImageValidationReport,
+    SingleModePathRequest, SkippedImage,
+};
+use crate::license;
+use crate::license_file::{LicenseFileError, OfflineLicense};
+use crate::preprocess;
+use crate::profiles::ProfileState;
+use crate::tasks::{now_millis, TaskStatus};
+use crate::ws::DetectionResultItem;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use tokio::sync::Mutex;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
 
-/// 单模态检测请求
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SingleModeRequest {
-    pub mode: String,
-    pub modality: String,
-    pub images: Vec<String>,
+/// 取消当前检测任务（批量任务会取消全部子任务）
+#[tauri::command]
+pub async fn cancel_detection(
+    app: AppHandle,
+    task_id: String,
+    profiles: State<'_, ProfileState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    if let Some(result) = batch::cancel(&app, &client, &task_id).await {
+        return result;
+    }
+    client.cancel_task(&task_id).await
 }
 
-/// 融合模式请求中的图像对
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImagePair {
-    pub rgb: String,
-    pub ir: String,
-}
+// ===== 任务状态查询 =====
 
-/// 融合模式检测请求
+/// 任务状态（返回给前端）
 #[derive(Debug, Serialize, Deserialize)]
-pub struct FusionModeRequest {
-    pub mode: String,
-    pub pairs: Vec<ImagePair>,
-}
-
-/// 检测结果项（来自 Python 后端）
-#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DetectionResultItem {
-    pub mode: String,
-    pub result: String,
-    pub confidence: f64,
-    pub probabilities: Vec<f64>,
-    pub processing_time: u64,
-    pub error: Option<String>,  // 错误信息（当 result 为"error"时）
-    pub image_index: Option<u32>,  // 图片在批次中的索引
-}
-
-/// 异步任务响应
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AsyncTaskResponse {
+pub struct TaskStatusResponse {
     pub task_id: String,
-    pub message: String,
+    pub status: String,
+    pub total_items: u32,
+    pub processed_items: u32,
+    pub message: Option<String>,
+    pub results: Vec<DetectionResultItem>,
 }
 
-/// WebSocket 连接响应
+/// 活跃任务摘要（返回给前端）
 #[derive(Debug, Serialize, Deserialize)]
-pub struct WsConnectedMessage {
-    #[serde(rename = "type")]
-    pub msg_type: String,
-    #[serde(rename = "client_id")]
-    pub client_id: String,
-}
-
-/// WebSocket 消息（转发给前端）
-#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WsEventMessage {
-    pub event_type: String,
+pub struct TaskSummary {
     pub task_id: String,
-    pub status: Option<String>,
-    pub message: Option<String>,
-    pub result: Option<DetectionResultItem>,
-    pub total_items: Option<u32>,
-    pub processed_items: Option<u32>,
-    pub completed_results: Option<Vec<DetectionResultItem>>,
+    pub mode: Option<String>,
+    pub status: String,
+    pub total_items: u32,
+    pub processed_items: u32,
+    pub client_id: Option<String>,
+    pub created_at: Option<String>,
 }
 
-/// WebSocket 连接状态
-pub struct WsConnectionState {
-    pub client_id: Option<String>,
-    pub is_connected: bool,
-    pub abort_handle: Option<tokio::task::JoinHandle<()>>,
+/// 活跃任务列表（返回给前端）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskListResponse {
+    pub total: u32,
+    pub tasks: Vec<TaskSummary>,
 }
 
-impl WsConnectionState {
-    pub fn new() -> Self {
-        Self {
-            client_id: None,
-            is_connected: false,
-            abort_handle: None,
+impl From<BackendTaskStatusResponse> for TaskStatusResponse {
+    fn from(value: BackendTaskStatusResponse) -> Self {
+        TaskStatusResponse {
+            task_id: value.task_id,
+            status: value.status,
+            total_items: value.total_items,
+            processed_items: value.completed_items,
+            message: value.message,
+            results: value.results.into_iter().map(DetectionResultItem::from).collect(),
         }
     }
 }
 
-pub type WsConnectionStateRef = Arc<Mutex<WsConnectionState>>;
-
-fn get_api_base_url() -> String {
-    std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8000".to_string())
-}
-
-/// 连接 WebSocket 并持续监听消息，转发给前端
-/// 如果已有活跃连接，会先断开旧连接
-#[tauri::command]
-pub async fn connect_websocket(
-    app: AppHandle,
-    ws_state: State<'_, WsConnectionStateRef>,
-    api_key: String,
-) -> Result<String, String> {
-    log::info!("connect_websocket 被调用，api_key: {}...", &api_key[..std::cmp::min(8, api_key.len())]);
-
-    // 先断开旧连接（如果有）
-    {
-        let mut state = ws_state.lock().await;
-        if let Some(handle) = state.abort_handle.take() {
-            log::info!("断开旧的 WebSocket 连接");
-            handle.abort();
+impl From<BackendTaskSummary> for TaskSummary {
+    fn from(value: BackendTaskSummary) -> Self {
+        TaskSummary {
+            task_id: value.task_id,
+            mode: value.mode,
+            status: value.status,
+            total_items: value.total_items,
+            processed_items: value.completed_items,
+            client_id: value.client_id,
+            created_at: value.created_at,
         }
-        state.client_id = None;
-        state.is_connected = false;
-    }
-
-    let base_url = get_api_base_url()
-        .replace("http://", "ws://")
-        .replace("https://", "wss://");
-    // 在 URL 中添加 API Key 查询参数进行认证
-    let ws_url = format!("{}/infer/ws?api_key={}", base_url, api_key);
-
-    let (ws_stream, _) = connect_async(&ws_url)
-        .await
-        .map_err(|e| format!("WebSocket 连接失败：{}", e))?;
-
-    log::info!("WebSocket 连接已建立到 {}", &ws_url[..std::cmp::min(50, ws_url.len())]);
-
-    // 更新连接状态
-    {
-        let mut state = ws_state.lock().await;
-        state.is_connected = true;
     }
+}
 
-    // 发送连接成功事件（client_id 稍后确定）
-    // 将 ws_stream 移动到 tokio::spawn 中处理
-    let app_clone = app.clone();
-    let ws_state_inner = ws_state.inner().clone();
-
-    // 使用 tokio::spawn 启动监听任务，保存句柄用于取消
-    let listen_task = tokio::spawn(async move {
-        let (mut write, mut read) = ws_stream.split();
-        let client_id: Option<String>;
-
-        // 等待接收 client_id
-        log::info!("等待 Python 后端发送 client_id...");
-        match read.next().await {
-            Some(Ok(WsMessage::Text(text))) => {
-                log::info!("收到 WebSocket 文本消息：{}", &text[..std::cmp::min(text.len(), 200)]);
-                let msg: WsConnectedMessage = match serde_json::from_str(&text) {
-                    Ok(m) => m,
-                    Err(e) => {
-                        log::error!("解析连接消息失败：{}", e);
-                        log::error!("原始消息：{}", text);
-                        return;
-                    }
-                };
-                log::info!("解析消息：msg_type={}, client_id={}", msg.msg_type, msg.client_id);
-                if msg.msg_type == "connected" {
-                    client_id = Some(msg.client_id.clone());
-                    log::info!("WebSocket 已连接，client_id: {}", msg.client_id);
-                    log::info!("发送 ws_connected 事件到前端：{}", msg.client_id);
-                    let _ = app_clone.emit("ws_connected", &msg.client_id);
-                    log::info!("ws_connected 事件发送完成");
-                } else {
-                    log::error!("未收到正确的连接确认消息，msg_type: {}", msg.msg_type);
-                    return;
-                }
-            }
-            Some(Ok(msg)) => {
-                log::error!("收到意外的 WebSocket 消息：{:?}", msg);
-                return;
-            }
-            Some(Err(e)) => {
-                log::error!("WebSocket 读取错误：{}", e);
-                return;
-            }
-            None => {
-                log::error!("WebSocket 连接意外关闭");
-                return;
-            }
-        }
-
-        // 收到 client_id 后更新状态
-        if let Some(ref cid) = client_id {
-            let mut state = ws_state_inner.lock().await;
-            state.client_id = Some(cid.clone());
-        }
-
-        let mut heartbeat_interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
-
-        loop {
-            tokio::select! {
-                _ = heartbeat_interval.tick() => {
-                    if write.send(WsMessage::Text("ping".to_string())).await.is_err() {
-                        log::info!("WebSocket 心跳发送失败，断开连接");
-                        break;
-                    }
-                }
-                msg = read.next() => {
-                    match msg {
-                        Some(Ok(WsMessage::Text(text))) => {
-                            if client_id.is_some() {
-                                let app_ref = app_clone.clone();
-                                if let Err(e) = handle_ws_message(&app_ref, &text) {
-                                    log::error!("处理 WebSocket 消息失败：{}", e);
-                                }
-                            }
-                        }
-                        Some(Ok(WsMessage::Close(_))) | None => {
-                            log::info!("WebSocket 连接关闭");
-                            break;
-                        }
-                        Some(Err(e)) => {
-                            log::error!("WebSocket 错误：{}", e);
-                            break;
-                        }
-                        _ => {}
-                    }
-                }
-            }
+impl From<BackendTaskListResponse> for TaskListResponse {
+    fn from(value: BackendTaskListResponse) -> Self {
+        TaskListResponse {
+            total: value.total,
+            tasks: value.tasks.into_iter().map(TaskSummary::from).collect(),
         }
-
-        // 连接断开后更新状态
-        let mut state = ws_state_inner.lock().await;
-        state.client_id = None;
-        state.is_connected = false;
-        state.abort_handle = None;
-        let _ = app_clone.emit("ws_disconnected", ());
-    });
-
-    // 保存任务句柄
-    {
-        let mut state = ws_state.lock().await;
-        state.abort_handle = Some(listen_task);
     }
-
-    // 返回临时的 client_id，实际 client_id 由监听任务确定
-    // 前端通过 ws_connected 事件获取真正的 client_id
-    Ok("pending".to_string())
 }
 
-/// 取消当前检测任务
+/// 查询单个任务的状态和已完成结果
 #[tauri::command]
-pub async fn cancel_detection(
+pub async fn get_task_status(
     task_id: String,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<AsyncTaskResponse, String> {
-    let api_url = format!("{}/infer/task/{}", get_api_base_url(), task_id);
-
-    log::info!("发送取消任务请求，task_id: {}", task_id);
-
-    let response = http_client
-        .delete(&api_url)
-        .header("X-API-Key", &api_key)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("取消任务失败 ({}): {}", status, error_text));
-    }
-
-    let res_text = response.text().await.map_err(|e| format!("解析响应失败：{}", e))?;
-    let task_response: AsyncTaskResponse = serde_json::from_str(&res_text)
-        .map_err(|e| format!("解析响应失败：{}", e))?;
+    profiles: State<'_, ProfileState>,
+) -> Result<TaskStatusResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let backend_response = client.task_status(&task_id).await?;
 
-    Ok(task_response)
-}
-
-/// 处理收到的 WebSocket 消息并转发给前端
-fn handle_ws_message(app: &AppHandle, text: &str) -> Result<(), String> {
-    let value: serde_json::Value = serde_json::from_str(text)
-        .map_err(|e| format!("解析 JSON 失败：{}", e))?;
-
-    let msg_type = value.get("type")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    log::info!("收到 WebSocket 消息类型：{}, 完整消息：{}", msg_type, text);
-
-    match msg_type {
-        "task_completed" | "task_partial_failure" => {
-            log::info!("收到 {} 消息", msg_type);
-            let data = value.get("data").ok_or("缺少 data 字段")?;
-            let task_id = data.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
-            log::info!("任务 ID: {}", task_id);
-
-            // 解析 completed_results（如果存在）
-            let completed_results = data.get("completed_results")
-                .and_then(|v| v.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|item| {
-                            serde_json::from_value::<DetectionResultItem>(item.clone()).ok()
-                        })
-                        .collect::<Vec<_>>()
-                });
-
-            log::info!("解析 completed_results: {:?}", completed_results.as_ref().map(|r| r.len()));
-
-            let event = WsEventMessage {
-                event_type: "task_completed".to_string(),
-                task_id: task_id.to_string(),
-                status: Some(data.get("status").and_then(|v| v.as_str()).unwrap_or("completed").to_string()),
-                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                result: None,
-                total_items: data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32),
-                processed_items: data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32),
-                completed_results,
-            };
-
-            log::info!("发送 ws_task_completed 事件到前端");
-            if let Err(e) = app.emit("ws_task_completed", &event) {
-                log::error!("发送事件失败：{}", e);
-            } else {
-                log::info!("任务完成事件已发送到前端：{}", event.task_id);
-            }
-        }
-        "task_failed" => {
-            let data = value.get("data").ok_or("缺少 data 字段")?;
-            let event = WsEventMessage {
-                event_type: "task_failed".to_string(),
-                task_id: data.get("task_id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                status: Some("failed".to_string()),
-                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                result: None,
-                total_items: None,
-                processed_items: None,
-                completed_results: None,
-            };
-            let _ = app.emit("ws_task_failed", &event);
-            log::error!("任务失败：{}", event.task_id);
-        }
-        "task_cancelled" => {
-            // 任务被取消
-            let data = value.get("data").ok_or("缺少 data 字段")?;
-            let event = WsEventMessage {
-                event_type: "task_completed".to_string(),
-                task_id: data.get("task_id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                status: Some("cancelled".to_string()),
-                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                result: None,
-                total_items: data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32),
-                processed_items: data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32),
-                completed_results: None,
-            };
-            let _ = app.emit("ws_task_completed", &event);
-            log::info!("任务已取消：{}", event.task_id);
-        }
-        "progress_update" | "progress" => {
-            log::info!("收到进度更新消息：{}", text);
-            let data = value.get("data").ok_or("缺少 data 字段")?;
-            let result = data.get("current_result").and_then(|r| r.as_object()).map(|r| DetectionResultItem {
-                mode: r.get("mode").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                result: r.get("result").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                confidence: r.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.0),
-                probabilities: r.get("probabilities")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
-                    .unwrap_or_default(),
-                processing_time: r.get("processing_time").and_then(|v| v.as_u64()).unwrap_or(0),
-                error: r.get("error").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                image_index: r.get("image_index").and_then(|v| v.as_u64()).map(|v| v as u32),
-            });
-            log::info!("解析结果：{:?}", result.as_ref().map(|r| (&r.result, &r.error, &r.image_index)));
-
-            let total = data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32);
-            let current = data.get("completed_items").and_then(|v| v.as_u64()).map(|v| v as u32);
-            let progress = if let (Some(t), Some(c)) = (total, current) {
-                if t > 0 { (c as f64 / t as f64 * 100.0) as u32 } else { 0 }
-            } else {
-                0
-            };
-
-            let event = WsEventMessage {
-                event_type: "progress".to_string(),
-                task_id: data.get("task_id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                status: Some("running".to_string()),
-                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                result,
-                total_items: total,
-                processed_items: current,
-                completed_results: None,
-            };
-            let _ = app.emit("ws_progress", &event);
-            log::debug!("进度：{}% - {}", progress, event.message.as_deref().unwrap_or(""));
-        }
-        _ => {
-            log::debug!("收到未知类型消息：{}", msg_type);
-        }
-    }
-
-    Ok(())
+    log::info!("任务 {} 状态：{}", task_id, backend_response.status);
+    Ok(backend_response.into())
 }
 
-/// 获取连接状态
+/// 列出当前 API Key 下的活跃任务
 #[tauri::command]
-pub fn get_ws_status(
-    ws_state: State<'_, WsConnectionStateRef>,
-) -> Result<(Option<String>, bool), String> {
-    let state = ws_state.blocking_lock();
-    Ok((state.client_id.clone(), state.is_connected))
+pub async fn list_active_tasks(
+    profiles: State<'_, ProfileState>,
+) -> Result<TaskListResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let backend_response = client.list_tasks().await?;
+
+    log::info!("活跃任务数量：{}", backend_response.tasks.len());
+    Ok(backend_response.into())
 }
 
 /// 单模态活体检测命令（异步模式）
+///
+/// 图片数量超过 batch.max_images_per_task 时拆分为多个子任务，返回批量任务 ID。
+/// Base64 提交的任务不重试结果为 error 的图片（开启 retry.image_max_attempts 时响应消息中会说明），
+/// 需要重试时使用 detect_single_mode_paths。
 #[tauri::command]
 pub async fn detect_single_mode_async(
+    app: AppHandle,
     request: SingleModeRequest,
     client_id: String,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<AsyncTaskResponse, String> {
-    let api_url = format!("{}/infer/single", get_api_base_url());
-
-    log::info!("发送单模态推理请求到：{}", api_url);
-    log::info!("client_id: {}, 图片数量：{}", client_id, request.images.len());
-
-    let response = http_client
-        .post(&api_url)
-        .header("X-Client-Id", &client_id)
-        .header("X-API-Key", &api_key)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("推理请求失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
-    }
-
-    // 先读取响应文本用于调试
-    let response_text = response.text().await.map_err(|e| format!("读取响应失败：{}", e))?;
-    log::info!("推理响应原始内容：{}", response_text);
-
-    // 解析 JSON
-    let task_response: AsyncTaskResponse = serde_json::from_str(&response_text)
-        .map_err(|e| format!("解析响应失败：{}, 响应内容：{}", e, response_text))?;
-
-    log::info!("任务创建成功：task_id={}", task_response.task_id);
-    Ok(task_response)
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let request = preprocess::preprocess_single_request(request, &config.0.image.preprocess).await?;
+    batch::submit(&app, &client, &client_id, BatchPayload::Single(request), &config.0.batch).await
 }
 
 /// 融合模式活体检测命令（异步模式）
+///
+/// 与 detect_single_mode_async 相同，不重试结果为 error 的图片对，需要重试时使用 detect_fusion_mode_paths。
 #[tauri::command]
 pub async fn detect_fusion_mode_async(
+    app: AppHandle,
     request: FusionModeRequest,
     client_id: String,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<AsyncTaskResponse, String> {
-    let api_url = format!("{}/infer/fusion", get_api_base_url());
-
-    log::info!("发送融合模式推理请求到：{}", api_url);
-    log::info!("client_id: {}, 图像对数量：{}", client_id, request.pairs.len());
-
-    let response = http_client
-        .post(&api_url)
-        .header("X-Client-Id", &client_id)
-        .header("X-API-Key", &api_key)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let request = preprocess::preprocess_fusion_request(request, &config.0.image.preprocess).await?;
+    batch::submit(&app, &client, &client_id, BatchPayload::Fusion(request), &config.0.batch).await
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("推理请求失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
+/// 在阻塞线程池中检查全部图片，避免读取文件阻塞异步运行时
+async fn check_files(paths: Vec<String>, config: &ConfigState) -> Result<(), AppError> {
+    let config = config.0.clone();
+    tokio::task::spawn_blocking(move || {
+        image_files::check_image_files(paths.iter().map(String::as_str), &config.image)
+    })
+    .await
+    .map_err(|e| AppError::validation(format!("检查图片失败：{}", e)))?
+}
+
+/// 单模态活体检测命令（传入图片路径，由 Rust 端读取、检查并编码）
+#[tauri::command]
+pub async fn detect_single_mode_paths(
+    app: AppHandle,
+    request: SingleModePathRequest,
+    client_id: String,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    if request.image_paths.is_empty() {
+        return Err(AppError::validation("请先选择图片"));
     }
+    check_files(request.image_paths.clone(), &config).await?;
+
+    let client = profiles.authorized_client()?;
+    let payload = BatchPayload::SinglePaths(request, config.0.image.preprocess.clone());
+    batch::submit(&app, &client, &client_id, payload, &config.0.batch).await
+}
+
+/// 融合模式活体检测命令（传入图片路径对，由 Rust 端读取、检查并编码）
+#[tauri::command]
+pub async fn detect_fusion_mode_paths(
+    app: AppHandle,
+    request: FusionModePathRequest,
+    client_id: String,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<AsyncTaskResponse, AppError> {
+    if request.pairs.is_empty() {
+        return Err(AppError::validation("未找到可配对的 RGB 和 IR 图片"));
+    }
+    let paths = request
+        .pairs
+        .iter()
+        .flat_map(|pair| [pair.rgb.clone(), pair.ir.clone()])
+        .collect();
+    check_files(paths, &config).await?;
+
+    let client = profiles.authorized_client()?;
+    let payload = BatchPayload::FusionPaths(request, config.0.image.preprocess.clone());
+    batch::submit(&app, &client, &client_id, payload, &config.0.batch).await
+}
 
-    // 先读取响应文本用于调试
-    let response_text = response.text().await.map_err(|e| format!("读取响应失败：{}", e))?;
-    log::info!("推理响应原始内容：{}", response_text);
+// ===== 文件夹批量检测 =====
 
-    // 解析 JSON
-    let task_response: AsyncTaskResponse = serde_json::from_str(&response_text)
-        .map_err(|e| format!("解析响应失败：{}, 响应内容：{}", e, response_text))?;
+/// 文件夹批量检测结果（返回给前端）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderDetectResponse {
+    /// 检测任务（图片较多时为批量任务）
+    pub task: AsyncTaskResponse,
+    /// 已提交的图片数量
+    pub submitted: usize,
+    pub skipped: Vec<SkippedImage>,
+}
+
+async fn scan_folder(
+    folder_path: String,
+    recursive: bool,
+    config: &ConfigState,
+) -> Result<FolderScanResult, AppError> {
+    let config = config.0.clone();
+    tokio::task::spawn_blocking(move || {
+        image_files::scan_image_folder(Path::new(&folder_path), recursive, &config.image, &config.batch)
+    })
+    .await
+    .map_err(|e| AppError::validation(format!("扫描文件夹失败：{}", e)))?
+}
 
-    log::info!("任务创建成功：task_id={}", task_response.task_id);
-    Ok(task_response)
+/// 扫描文件夹中可检测的图片（不提交），用于提交前预览
+#[tauri::command]
+pub async fn scan_image_folder(
+    folder_path: String,
+    recursive: Option<bool>,
+    config: State<'_, ConfigState>,
+) -> Result<FolderScanResult, AppError> {
+    scan_folder(folder_path, recursive.unwrap_or(true), &config).await
+}
+
+/// 文件夹批量检测：扫描并检查图片后提交，图片较多时按 batch 配置拆分为多个子任务
+#[tauri::command]
+pub async fn detect_folder(
+    app: AppHandle,
+    request: FolderDetectRequest,
+    client_id: String,
+    profiles: State<'_, ProfileState>,
+    config: State<'_, ConfigState>,
+) -> Result<FolderDetectResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let scan = scan_folder(request.folder_path, request.recursive.unwrap_or(true), &config).await?;
+    if scan.images.is_empty() {
+        return Err(AppError::validation(format!(
+            "文件夹中没有可检测的图片（跳过 {} 个文件）",
+            scan.skipped.len()
+        )));
+    }
+
+    let image_paths: Vec<String> = scan.images.into_iter().map(|image| image.path).collect();
+    let submitted = image_paths.len();
+    let payload = BatchPayload::SinglePaths(
+        SingleModePathRequest {
+            mode: request.mode,
+            modality: request.modality,
+            image_paths,
+        },
+        config.0.image.preprocess.clone(),
+    );
+    let task = batch::submit(&app, &client, &client_id, payload, &config.0.batch).await?;
+
+    Ok(FolderDetectResponse {
+        task,
+        submitted,
+        skipped: scan.skipped,
+    })
 }
 
 /// 获取支持的图片格式
 #[tauri::command]
-pub fn get_supported_formats(config: State<'_, ConfigState>) -> Result<Vec<String>, String> {
+pub fn get_supported_formats(config: State<'_, ConfigState>) -> Result<Vec<String>, AppError> {
     Ok(config.0.image.supported_formats.clone())
 }
 
-/// 验证图片有效性
+/// 验证图片有效性（检查文件内容），返回格式、尺寸和文件大小
 #[tauri::command]
-pub fn validate_image(
+pub async fn validate_image(
     image_path: String,
     config: State<'_, ConfigState>,
-) -> Result<bool, String> {
-    use std::path::Path;
-
-    let supported_formats = get_supported_formats(config)?;
-    let path = Path::new(&image_path);
-
-    if !path.exists() {
-        return Err(format!("文件不存在：{}", image_path));
-    }
-
-    if !path.is_file() {
-        return Err(format!("路径不是文件：{}", image_path));
-    }
-
-    let extension = path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase());
-
-    match extension {
-        Some(ext) => {
-            if supported_formats.contains(&ext) {
-                Ok(true)
-            } else {
-                Err(format!(
-                    "不支持的图片格式：{}. 支持的格式：{}",
-                    ext,
-                    supported_formats.join(", ")
-                ))
-            }
-        }
-        None => Err(format!("无法获取文件扩展名：{}", image_path)),
-    }
+) -> Result<ImageValidationReport, AppError> {
+    let config = config.0.clone();
+    tokio::task::spawn_blocking(move || image_files::check_image_file(Path::new(&image_path), &config.image))
+        .await
+        .map_err(|e| AppError::validation(format!("检查图片失败：{}", e)))?
 }
 
-// ===== 激活码验证 =====
+/// 预览缩略图的最长边（像素）
+const PREVIEW_MAX_EDGE: u32 = 320;
 
-/// 激活码验证请求（发送到后端）
-#[derive(Debug, Serialize)]
-struct BackendActivateRequest {
-    code: String,
+/// 生成本地图片的预览缩略图（JPEG data URL），只通过 IPC 传输缩略图
+#[tauri::command]
+pub async fn get_image_preview(image_path: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || image_files::preview_image(Path::new(&image_path), PREVIEW_MAX_EDGE))
+        .await
+        .map_err(|e| AppError::validation(format!("生成图片预览失败：{}", e)))?
 }
 
-/// 激活码验证响应（从后端返回）
-#[derive(Debug, Serialize, Deserialize)]
-struct BackendActivateResponse {
-    api_key: String,
-    message: String,
-    expires_at: Option<String>,
-}
+// ===== 激活码验证 =====
 
 /// 激活码验证请求（前端传入）
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivateRequest {
+    #[serde(default)]
     pub activation_code: String,
+    /// 离线授权文件内容，提供时在本地验证签名，不请求后端
+    #[serde(default)]
+    pub license_file: Option<String>,
 }
 
-/// 激活码验证响应（返回给前端）
-#[derive(Debug, Serialize, Deserialize)]
+/// 激活被拒绝的原因（激活码格式错误或离线授权文件无效）
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ActivationRejection {
+    Code(ActivationCodeError),
+    LicenseFile(LicenseFileError),
+}
+
+/// 激活码验证响应（返回给前端，不包含 API Key）
+#[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivateResponse {
     pub success: bool,
     pub message: String,
-    pub api_key: Option<String>,
     pub expires_at: Option<String>,
+    /// 本地校验未通过的具体原因（此时不会请求后端）
+    pub rejection: Option<ActivationRejection>,
 }
 
-/// 验证激活码并获取 API Key
-#[tauri::command]
-pub async fn activate_license(
-    request: ActivateRequest,
-    http_client: State<'_, Client>,
-) -> Result<ActivateResponse, String> {
-    let code = &request.activation_code;
-
-    // 简单的格式验证：以 ACT- 开头
-    if !code.starts_with("ACT-") || code.len() < 5 {
-        return Ok(ActivateResponse {
+impl ActivateResponse {
+    fn rejected(message: String, rejection: Option<ActivationRejection>) -> Self {
+        ActivateResponse {
             success: false,
-            message: "激活码格式不正确".to_string(),
-            api_key: None,
+            message,
             expires_at: None,
-        });
+            rejection,
+        }
     }
+}
 
-    // 调用后端 API 验证激活码
-    let api_url = format!("{}/auth/activate", get_api_base_url());
+/// 将激活得到的 API Key 保存到密钥环和当前服务器配置，并记录授权到期时间
+fn save_activation(
+    app: &AppHandle,
+    profiles: &ProfileState,
+    api_key: String,
+    expires_at: Option<String>,
+) -> Result<(), AppError> {
+    let profile = profiles.active_profile();
+    credentials::save_profile_api_key(&profile.id, &api_key)?;
+    profiles.set_api_key(&profile.id, Some(api_key));
+    license::record_activation(app, &profile.id, expires_at)
+}
 
-    let backend_request = BackendActivateRequest {
-        code: code.clone(),
+/// 验证激活码或离线授权文件，获取的 API Key 直接保存到密钥环和当前服务器配置
+#[tauri::command]
+pub async fn activate_license(
+    app: AppHandle,
+    request: ActivateRequest,
+    profiles: State<'_, ProfileState>,
+) -> Result<ActivateResponse, AppError> {
+    if let Some(ref content) = request.license_file {
+        return activate_with_license_file(&app, &profiles, content);
+    }
+
+    // 先在本地解析并规范化激活码，格式错误时不请求后端
+    let code = match ActivationCode::parse(&request.activation_code) {
+        Ok(code) => code,
+        Err(rejection) => {
+            log::info!("激活码格式校验未通过：{}", rejection);
+            return Ok(ActivateResponse::rejected(
+                rejection.to_string(),
+                Some(ActivationRejection::Code(rejection)),
+            ));
+        }
     };
 
-    // 记录详细日志用于调试
-    log::info!("发送激活码验证请求到：{}", api_url);
-    log::info!("激活码：{}... (长度：{}, 字节：{:?})",
-               &code[..std::cmp::min(8, code.len())],
-               code.len(),
-               code.as_bytes());
-
-    let response = http_client
-        .post(&api_url)
-        .header("Content-Type", "application/json")
-        .header("User-Agent", "frontend-tauri-app/0.1.0")
-        .json(&backend_request)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
+    log::info!("激活码：{}", code.masked());
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("激活码验证失败：{} - {}", status, error_text);
-        return Ok(ActivateResponse {
-            success: false,
-            message: format!("服务器返回错误 ({}): {}", status, error_text),
-            api_key: None,
-            expires_at: None,
-        });
-    }
+    // 调用后端 API 验证激活码
+    let client = profiles.public_client();
+    let backend_response = match client.activate(code.as_str()).await {
+        Ok(response) => response,
+        Err(e) if e.status.is_some() => {
+            return Ok(ActivateResponse::rejected(e.to_string(), None));
+        }
+        Err(e) => return Err(e),
+    };
 
-    let backend_response: BackendActivateResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("解析响应失败：{}", e))?;
+    log::info!("激活码 {} 激活成功：{}", code.masked(), backend_response.message);
 
-    log::info!("激活码 {} 激活成功，用户 ID: {}", &code[..8], backend_response.message);
+    save_activation(
+        &app,
+        &profiles,
+        backend_response.api_key,
+        backend_response.expires_at.clone(),
+    )?;
 
     Ok(ActivateResponse {
         success: true,
         message: backend_response.message,
-        api_key: Some(backend_response.api_key),
         expires_at: backend_response.expires_at,
+        rejection: None,
     })
 }
 
-// ===== API Key 安全存储（系统密钥环） =====
-
-/// 将 API Key 存储到系统密钥环
-#[tauri::command]
-pub fn store_api_key(api_key: String) -> Result<(), String> {
-    use keyring::Entry;
-
-    log::info!("存储 API Key 到系统密钥环");
-
-    let entry = Entry::new("face-detection-app", "api_key")
-        .map_err(|e| format!("创建密钥环条目失败：{}", e))?;
-
-    entry.set_password(&api_key)
-        .map_err(|e| format!("存储 API Key 失败：{}", e))?;
-
-    log::info!("API Key 已成功存储到系统密钥环");
-    Ok(())
-}
-
-/// 从系统密钥环检索 API Key
-#[tauri::command]
-pub fn retrieve_api_key() -> Result<Option<String>, String> {
-    use keyring::Entry;
-
-    log::info!("从系统密钥环检索 API Key");
-
-    let entry = Entry::new("face-detection-app", "api_key")
-        .map_err(|e| format!("创建密钥环条目失败：{}", e))?;
-
-    match entry.get_password() {
-        Ok(key) => {
-            log::info!("成功从密钥环检索到 API Key");
-            Ok(Some(key))
-        }
-        Err(keyring::Error::NoEntry) => {
-            log::info!("密钥环中无 API Key 记录");
-            Ok(None)
+/// 离线激活：使用内置公钥验证授权文件签名，从中取得 API Key 和到期时间
+fn activate_with_license_file(
+    app: &AppHandle,
+    profiles: &ProfileState,
+    content: &str,
+) -> Result<ActivateResponse, AppError> {
+    let base_url = profiles.active_profile().base_url;
+    let license = match OfflineLicense::verify(content, &base_url) {
+        Ok(license) => license,
+        Err(rejection) => {
+            log::warn!("离线授权文件验证失败：{}", rejection);
+            return Ok(ActivateResponse::rejected(
+                rejection.to_string(),
+                Some(ActivationRejection::LicenseFile(rejection)),
+            ));
         }
-        Err(e) => Err(format!("从密钥环读取失败：{}", e)),
-    }
-}
-
-/// 从系统密钥环删除 API Key
-#[tauri::command]
-pub fn delete_api_key() -> Result<(), String> {
-    use keyring::Entry;
-
-    log::info!("从系统密钥环删除 API Key");
+    };
 
-    let entry = Entry::new("face-detection-app", "api_key")
-        .map_err(|e| format!("创建密钥环条目失败：{}", e))?;
+    log::info!(
+        "离线授权文件 {} 验证成功（客户：{}，签发时间：{}）",
+        license.license_id,
+        license.customer,
+        license.issued_at
+    );
 
-    entry.delete_credential()
-        .map_err(|e| format!("删除 API Key 失败：{}", e))?;
+    save_activation(app, profiles, license.api_key, license.expires_at.clone())?;
 
-    log::info!("API Key 已从密钥环删除");
-    Ok(())
+    Ok(ActivateResponse {
+        success: true,
+        message: "离线授权激活成功".to_string(),
+        expires_at: license.expires_at,
+        rejection: None,
+    })
 }
 
 // ===== 历史记录 API =====
 
-/// 历史查询参数
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HistoryQueryParams {
-    pub client_id: Option<String>,
-    pub mode: Option<String>,
-    pub status: Option<String>,
-    pub days: Option<String>,
-    pub page: Option<String>,
-    pub page_size: Option<String>,
-}
-
-/// 历史统计参数
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HistoryStatsParams {
-    pub client_id: Option<String>,
-    pub days: Option<String>,
-}
-
-/// 历史删除参数
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HistoryDeleteParams {
-    pub task_ids: Option<Vec<String>>,
-    pub days_ago: Option<u32>,
-}
-
-/// 历史结果项（从后端返回）
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BackendHistoryResultItem {
-    pub mode: String,
-    pub modality: Option<String>,
-    pub result: String,
-    pub confidence: f64,
-    pub probabilities: Vec<f64>,
-    pub processing_time: u64,
-    pub image_index: Option<u32>,
-    pub error: Option<String>,
-    pub retry_count: u32,
-}
-
-/// 历史任务项（从后端返回）
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(default)]
-pub struct BackendHistoryTaskItem {
-    pub task_id: String,
-    pub client_id: Option<String>,
-    pub api_key_hash: Option<String>,
-    pub mode: String,
-    pub status: String,
-    pub total_items: u32,
-    pub successful_items: u32,
-    pub failed_items: u32,
-    pub real_count: u32,
-    pub fake_count: u32,
-    pub elapsed_time_ms: u64,
-    pub created_at: String,
-    pub completed_at: Option<String>,
-    pub results: Option<Vec<BackendHistoryResultItem>>,
-}
-
-/// 历史查询响应（从后端返回）
-#[derive(Debug, Serialize, Deserialize, Default)]
-#[serde(default)]
-pub struct BackendHistoryQueryResponse {
-    pub total: u32,
-    pub page: u32,
-    pub page_size: u32,
-    pub total_pages: u32,
-    pub items: Vec<BackendHistoryTaskItem>,
-}
-
-/// 历史统计响应（从后端返回）
-#[derive(Debug, Serialize, Deserialize, Default)]
-#[serde(default)]
-pub struct BackendHistoryStatsResponse {
-    pub total_tasks: u32,
-    pub total_inferences: u32,
-    pub total_real: u32,
-    pub total_fake: u32,
-    pub total_errors: u32,
-    pub success_rate: f64,
-    pub avg_processing_time_ms: f64,
-    pub date_range: Option<BackendDateRange>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct BackendDateRange {
-    pub start: Option<String>,
-    pub end: Option<String>,
-}
-
-/// 历史删除响应（从后端返回）
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BackendHistoryDeleteResponse {
-    pub deleted_count: u32,
-    pub message: String,
-}
-
-/// 获取所有历史记录响应（从后端返回）
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BackendHistoryAllResponse {
-    pub total: u32,
-    pub api_key_hash: String,
-    pub items: Vec<BackendHistoryTaskItem>,
-}
-
 /// 历史查询响应（返回给前端）
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -929,6 +592,50 @@ impl From<BackendHistoryTaskItem> for HistoryTaskItem {
     }
 }
 
+/// 历史任务详情缓存上限
+const HISTORY_CACHE_CAPACITY: usize = 200;
+
+/// 历史任务详情缓存（按服务器配置和 task_id 区分，只缓存已结束的任务）
+#[derive(Default)]
+pub struct HistoryCache(Mutex<HashMap<(String, String), (u64, HistoryTaskItem)>>);
+
+impl HistoryCache {
+    fn get(&self, profile_id: &str, task_id: &str) -> Option<HistoryTaskItem> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(profile_id.to_string(), task_id.to_string()))
+            .map(|(_, item)| item.clone())
+    }
+
+    fn insert(&self, profile_id: &str, item: HistoryTaskItem) {
+        let mut cache = self.0.lock().unwrap();
+        if cache.len() >= HISTORY_CACHE_CAPACITY {
+            // 淘汰最早缓存的一项
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (cached_at, _))| *cached_at)
+                .map(|(key, _)| key.clone())
+            {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(
+            (profile_id.to_string(), item.task_id.clone()),
+            (now_millis(), item),
+        );
+    }
+
+    /// 移除指定服务器配置下对应任务的缓存，未指定任务时移除该配置的全部缓存
+    pub(crate) fn invalidate(&self, profile_id: &str, task_ids: Option<&[String]>) {
+        let mut cache = self.0.lock().unwrap();
+        cache.retain(|(cached_profile_id, task_id), _| {
+            cached_profile_id != profile_id
+                || task_ids.is_some_and(|task_ids| !task_ids.contains(task_id))
+        });
+    }
+}
+
 /// 将后端统计响应转换为前端格式
 impl From<BackendHistoryStatsResponse> for HistoryStatsResponse {
     fn from(value: BackendHistoryStatsResponse) -> Self {
@@ -952,91 +659,12 @@ impl From<BackendHistoryStatsResponse> for HistoryStatsResponse {
 #[tauri::command]
 pub async fn query_history(
     params: HistoryQueryParams,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<HistoryQueryResponse, String> {
-    log::info!("query_history 被调用，params: {:?}, api_key 长度：{}", params, api_key.len());
-    log::info!("query_history api_key 前缀：{}", &api_key[..std::cmp::min(20, api_key.len())]);
-
-    let mut api_url = format!("{}/history", get_api_base_url());
-
-    // 构建查询参数
-    let mut query_params = Vec::new();
-    if let Some(ref client_id) = params.client_id {
-        query_params.push(("client_id", client_id.clone()));
-    }
-    if let Some(ref mode) = params.mode {
-        query_params.push(("mode", mode.clone()));
-    }
-    if let Some(ref status) = params.status {
-        query_params.push(("status", status.clone()));
-    }
-    // 解析数字参数（从字符串）
-    if let Some(ref days_str) = params.days {
-        if let Ok(days) = days_str.parse::<u32>() {
-            query_params.push(("days", days.to_string()));
-        }
-    }
-    if let Some(ref page_str) = params.page {
-        if let Ok(page) = page_str.parse::<u32>() {
-            query_params.push(("page", page.to_string()));
-        }
-    }
-    if let Some(ref page_size_str) = params.page_size {
-        if let Ok(page_size) = page_size_str.parse::<u32>() {
-            query_params.push(("page_size", page_size.to_string()));
-        }
-    }
-
-    if !query_params.is_empty() {
-        api_url.push('?');
-        api_url.push_str(
-            &query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join("&"),
-        );
-    }
-
-    log::info!("查询历史记录：{}", api_url);
-
-    log::info!("发送 HTTP 请求...");
-    let response = http_client
-        .get(&api_url)
-        .header("X-API-Key", &api_key)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("HTTP 请求失败：{}", e);
-            format!("网络请求失败：{}", e)
-        })?;
-
-    log::info!("收到 HTTP 响应，状态码：{}", response.status());
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("历史查询失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
-    }
-
-    log::info!("历史查询响应成功，解析 JSON...");
-
-    // 先获取原始文本用于调试
-    let response_text = response.text().await.map_err(|e| {
-        log::error!("读取响应文本失败：{}", e);
-        format!("读取响应失败：{}", e)
-    })?;
+    profiles: State<'_, ProfileState>,
+) -> Result<HistoryQueryResponse, AppError> {
+    log::info!("query_history 被调用，params: {:?}", params);
 
-    log::info!("后端返回的原始 JSON: {}", response_text);
-
-    // 手动解析 JSON 进行调试
-    let backend_response: BackendHistoryQueryResponse = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            log::error!("解析响应 JSON 失败：{}", e);
-            format!("解析响应失败：{}", e)
-        })?;
+    let client = profiles.authorized_client()?;
+    let backend_response = client.query_history(&params).await?;
 
     log::info!("解析成功，total: {}, items: {}", backend_response.total, backend_response.items.len());
 
@@ -1060,75 +688,10 @@ pub async fn query_history(
 #[tauri::command]
 pub async fn get_history_stats(
     params: Option<HistoryStatsParams>,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<HistoryStatsResponse, String> {
-    let mut api_url = format!("{}/history/stats", get_api_base_url());
-
-    // 构建查询参数
-    let mut query_params = Vec::new();
-    if let Some(ref p) = params {
-        // 添加 client_id 参数
-        if let Some(ref client_id) = p.client_id {
-            query_params.push(("client_id", client_id.clone()));
-        }
-        // 解析 days 参数（从字符串）
-        if let Some(ref days_str) = p.days {
-            if let Ok(days) = days_str.parse::<u32>() {
-                query_params.push(("days", days.to_string()));
-            }
-        }
-    }
-
-    if !query_params.is_empty() {
-        api_url.push('?');
-        api_url.push_str(
-            &query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join("&"),
-        );
-    }
-
-    log::info!("获取历史统计：{}", api_url);
-
-    log::info!("发送 HTTP 请求（统计）...");
-    let response = http_client
-        .get(&api_url)
-        .header("X-API-Key", &api_key)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("统计 HTTP 请求失败：{}", e);
-            format!("网络请求失败：{}", e)
-        })?;
-
-    log::info!("收到统计 HTTP 响应，状态码：{}", response.status());
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("统计查询失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
-    }
-
-    log::info!("统计查询响应成功，解析 JSON...");
-
-    // 先获取原始文本用于调试
-    let response_text = response.text().await.map_err(|e| {
-        log::error!("读取统计响应文本失败：{}", e);
-        format!("读取响应失败：{}", e)
-    })?;
-
-    log::info!("统计后端返回的原始 JSON: {}", response_text);
-
-    // 手动解析 JSON 进行调试
-    let backend_response: BackendHistoryStatsResponse = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            log::error!("统计解析响应 JSON 失败：{}", e);
-            format!("解析响应失败：{}", e)
-        })?;
+    profiles: State<'_, ProfileState>,
+) -> Result<HistoryStatsResponse, AppError> {
+    let client = profiles.authorized_client()?;
+    let backend_response = client.history_stats(params.as_ref()).await?;
 
     log::info!("统计解析成功，total_tasks: {}", backend_response.total_tasks);
 
@@ -1139,51 +702,15 @@ pub async fn get_history_stats(
 #[tauri::command]
 pub async fn delete_history(
     params: HistoryDeleteParams,
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<HistoryDeleteResponse, String> {
-    let mut api_url = format!("{}/history", get_api_base_url());
-
-    // 构建查询参数
-    let mut query_params = Vec::new();
-    if let Some(ref task_ids) = params.task_ids {
-        query_params.push(("task_ids", task_ids.join(",")));
-    }
-    if let Some(days_ago) = params.days_ago {
-        query_params.push(("days_ago", days_ago.to_string()));
-    }
-
-    if !query_params.is_empty() {
-        api_url.push('?');
-        api_url.push_str(
-            &query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<String>>()
-                .join("&"),
-        );
-    }
+    profiles: State<'_, ProfileState>,
+    history_cache: State<'_, HistoryCache>,
+) -> Result<HistoryDeleteResponse, AppError> {
+    let profile_id = profiles.active_profile().id;
+    let client = profiles.authorized_client()?;
+    let backend_response = client.delete_history(&params).await?;
+    history_cache.invalidate(&profile_id, params.task_ids.as_deref());
 
-    log::info!("删除历史记录：{}", api_url);
-
-    let response = http_client
-        .delete(&api_url)
-        .header("X-API-Key", &api_key)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("历史删除失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
-    }
-
-    let backend_response: BackendHistoryDeleteResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("解析响应失败：{}", e))?;
+    log::info!("已删除 {} 条历史记录", backend_response.deleted_count);
 
     Ok(HistoryDeleteResponse {
         success: true,
@@ -1191,45 +718,43 @@ pub async fn delete_history(
     })
 }
 
-/// 获取所有历史记录（无分页）
+/// 获取单个历史任务的完整详情（包含每张图片的结果）
+///
+/// 已结束的任务详情会被缓存，再次打开时直接返回；force_refresh 为 true 时重新请求。
+/// 先检查当前服务器是否已激活，未激活时不返回缓存。
 #[tauri::command]
-pub async fn get_all_history(
-    api_key: String,
-    http_client: State<'_, Client>,
-) -> Result<HistoryAllResponse, String> {
-    let api_url = format!("{}/history/all", get_api_base_url());
-
-    log::info!("获取所有历史记录：{}", api_url);
-    log::info!("API Key 长度：{}, 前缀：{}", api_key.len(), &api_key[..std::cmp::min(12, api_key.len())]);
+pub async fn get_history_task(
+    task_id: String,
+    force_refresh: Option<bool>,
+    profiles: State<'_, ProfileState>,
+    history_cache: State<'_, HistoryCache>,
+) -> Result<HistoryTaskItem, AppError> {
+    let profile_id = profiles.active_profile().id;
+    let client = profiles.authorized_client()?;
+    if !force_refresh.unwrap_or(false) {
+        if let Some(item) = history_cache.get(&profile_id, &task_id) {
+            log::debug!("历史任务 {} 命中缓存", task_id);
+            return Ok(item);
+        }
+    }
 
-    let response = http_client
-        .get(&api_url)
-        .header("X-API-Key", &api_key)
-        .send()
-        .await
-        .map_err(|e| format!("网络请求失败：{}", e))?;
+    let item = HistoryTaskItem::from(client.history_task(&task_id).await?);
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        log::error!("获取所有历史记录失败：{} - {}", status, error_text);
-        return Err(format!("服务器返回错误 ({}): {}", status, error_text));
+    if TaskStatus::from_backend(&item.status).is_terminal() {
+        history_cache.insert(&profile_id, item.clone());
     }
+    Ok(item)
+}
 
-    // 先获取原始文本用于调试
-    let response_text = response.text().await.map_err(|e| {
-        log::error!("读取响应文本失败：{}", e);
-        format!("读取响应失败：{}", e)
-    })?;
-
-    log::info!("后端返回的原始 JSON: {}", response_text);
+/// 获取所有历史记录（无分页）
+#[tauri::command]
+pub async fn get_all_history(
+    profiles: State<'_, ProfileState>,
+) -> Result<HistoryAllResponse, AppError> {
+    log::info!("get_all_history 被调用");
 
-    // 解析后端响应（后端返回 HistoryAllResponse 格式）
-    let backend_response: BackendHistoryAllResponse = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            log::error!("解析响应 JSON 失败：{}", e);
-            format!("解析响应失败：{}", e)
-        })?;
+    let client = profiles.authorized_client()?;
+    let backend_response = client.all_history().await?;
 
     log::info!("解析成功，total: {}, items: {}", backend_response.total, backend_response.items.len());
 
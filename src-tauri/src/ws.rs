@@ -0,0 +1,670 @@
+use crate::api::{ApiClient, BackendDetectionResultItem};
+use crate::batch;
+use crate::config::{AppConfig, ConfigState, TransportPreference, WebSocketConfig};
+use crate::error::AppError;
+use crate::profiles::ProfileState;
+use crate::tasks::{TaskRegistry, TaskStatus};
+use crate::transport::{self, TransportChangedEvent, TransportMode};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, tungstenite::Message as WsMessage, Connector, MaybeTlsStream,
+    WebSocketStream,
+};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// 恢复任务状态时单个任务允许连续查询失败的次数，超过后标记为失败
+const MAX_RECOVERY_FAILURES: u32 = 30;
+
+/// 检测结果项（来自 Python 后端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionResultItem {
+    pub mode: String,
+    pub result: String,
+    pub confidence: f64,
+    pub probabilities: Vec<f64>,
+    pub processing_time: u64,
+    pub error: Option<String>,  // 错误信息（当 result 为"error"时）
+    pub image_index: Option<u32>,  // 图片在批次中的索引
+}
+
+/// WebSocket 连接响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WsConnectedMessage {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    #[serde(rename = "client_id")]
+    pub client_id: String,
+}
+
+/// WebSocket 消息（转发给前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsEventMessage {
+    pub event_type: String,
+    pub task_id: String,
+    pub status: Option<String>,
+    pub message: Option<String>,
+    pub result: Option<DetectionResultItem>,
+    pub total_items: Option<u32>,
+    pub processed_items: Option<u32>,
+    pub completed_results: Option<Vec<DetectionResultItem>>,
+}
+
+/// WebSocket 连接状态
+pub struct WsConnectionState {
+    pub client_id: Option<String>,
+    pub is_connected: bool,
+    pub abort_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 重连后补发任务结果的轮询任务
+    pub recovery_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 当前连续重连次数（连接成功后清零）
+    pub reconnect_attempts: u32,
+    /// 最近一次断线或重连失败的原因
+    pub last_error: Option<String>,
+    /// 当前推送方式
+    pub transport: TransportMode,
+}
+
+impl WsConnectionState {
+    pub fn new() -> Self {
+        Self {
+            client_id: None,
+            is_connected: false,
+            abort_handle: None,
+            recovery_handle: None,
+            reconnect_attempts: 0,
+            last_error: None,
+            transport: TransportMode::Websocket,
+        }
+    }
+}
+
+pub type WsConnectionStateRef = Arc<Mutex<WsConnectionState>>;
+
+/// 连接状态快照（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsStatus {
+    pub client_id: Option<String>,
+    pub is_connected: bool,
+    pub reconnect_attempts: u32,
+    pub last_error: Option<String>,
+    pub transport: TransportMode,
+}
+
+/// 正在重连事件（ws_reconnecting）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsReconnectingEvent {
+    pub attempt: u32,
+    /// 0 表示不限次数
+    pub max_attempts: u32,
+    pub delay_ms: u64,
+    pub last_error: Option<String>,
+}
+
+/// 重连成功事件（ws_reconnected）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsReconnectedEvent {
+    pub client_id: String,
+    pub attempts: u32,
+}
+
+/// 连接 WebSocket 并持续监听消息，转发给前端
+/// 如果已有活跃连接，会先断开旧连接；连接断开后由守护任务自动重连
+/// 推送方式为 auto 时，WebSocket 不可用则回退到 HTTP 轮询
+#[tauri::command]
+pub async fn connect_websocket(
+    app: AppHandle,
+    ws_state: State<'_, WsConnectionStateRef>,
+    config: State<'_, ConfigState>,
+    profiles: State<'_, ProfileState>,
+) -> Result<String, AppError> {
+    log::info!("connect_websocket 被调用");
+
+    // 在 URL 中添加 API Key 查询参数进行认证
+    let client = profiles.authorized_client()?;
+    disconnect(ws_state.inner()).await;
+
+    establish_connection(&app, ws_state.inner(), config.0.clone(), client, profiles.ws_connector()).await
+}
+
+/// 断开当前连接（包括守护任务和补发任务），重置连接状态
+pub(crate) async fn disconnect(ws_state: &WsConnectionStateRef) {
+    let mut state = ws_state.lock().await;
+    if let Some(handle) = state.abort_handle.take() {
+        log::info!("断开旧的 WebSocket 连接");
+        handle.abort();
+    }
+    if let Some(handle) = state.recovery_handle.take() {
+        handle.abort();
+    }
+    state.client_id = None;
+    state.is_connected = false;
+    state.reconnect_attempts = 0;
+    state.last_error = None;
+}
+
+/// 建立到指定后端的推送连接（WebSocket 或回退的 HTTP 轮询）
+/// WebSocket 模式下返回 "pending"，实际 client_id 通过 ws_connected 事件发送
+pub(crate) async fn establish_connection(
+    app: &AppHandle,
+    ws_state: &WsConnectionStateRef,
+    config: Arc<AppConfig>,
+    client: ApiClient,
+    connector: Option<Connector>,
+) -> Result<String, AppError> {
+    let ws_url = client.ws_url();
+    let transport_config = &config.transport;
+
+    if transport_config.mode == TransportPreference::Polling {
+        return Ok(transport::start_polling_transport(
+            app,
+            ws_state,
+            client,
+            transport_config.poll_interval_ms,
+            None,
+        )
+        .await);
+    }
+
+    let ws_stream = match connect_async_tls_with_config(&ws_url, None, false, connector.clone()).await {
+        Ok((stream, _)) => stream,
+        Err(e) if transport_config.mode == TransportPreference::Auto => {
+            log::error!("WebSocket 连接失败，回退到 HTTP 轮询：{}", e);
+            return Ok(transport::start_polling_transport(
+                app,
+                ws_state,
+                client,
+                transport_config.poll_interval_ms,
+                Some(format!("WebSocket 连接失败：{}", e)),
+            )
+            .await);
+        }
+        Err(e) => return Err(AppError::network(format!("WebSocket 连接失败：{}", e))),
+    };
+
+    log::info!("WebSocket 连接已建立到 {}", &ws_url[..std::cmp::min(50, ws_url.len())]);
+
+    // 更新连接状态
+    let previous_transport = {
+        let mut state = ws_state.lock().await;
+        state.is_connected = true;
+        std::mem::replace(&mut state.transport, TransportMode::Websocket)
+    };
+    if previous_transport != TransportMode::Websocket {
+        let _ = app.emit(
+            "transport_changed",
+            &TransportChangedEvent {
+                mode: TransportMode::Websocket,
+                reason: None,
+            },
+        );
+    }
+
+    // 启动守护任务，保存句柄用于取消
+    let supervisor = tokio::spawn(supervise_connection(
+        app.clone(),
+        ws_state.clone(),
+        config,
+        client,
+        WsTarget {
+            url: ws_url,
+            connector,
+        },
+        ws_stream,
+    ));
+
+    {
+        let mut state = ws_state.lock().await;
+        state.abort_handle = Some(supervisor);
+    }
+
+    // 返回临时的 client_id，实际 client_id 由监听任务确定
+    // 前端通过 ws_connected 事件获取真正的 client_id
+    Ok("pending".to_string())
+}
+
+/// WebSocket 连接目标（重连时复用）
+struct WsTarget {
+    url: String,
+    connector: Option<Connector>,
+}
+
+/// 连接守护任务：会话结束后按带抖动的指数退避自动重连，
+/// 超过最大重连次数后，auto 模式回退到 HTTP 轮询，否则发送 ws_disconnected 并退出
+async fn supervise_connection(
+    app: AppHandle,
+    ws_state: WsConnectionStateRef,
+    app_config: Arc<AppConfig>,
+    client: ApiClient,
+    target: WsTarget,
+    ws_stream: WsStream,
+) {
+    let config = &app_config.websocket;
+    let mut next_stream = Some(ws_stream);
+
+    loop {
+        if let Some(stream) = next_stream.take() {
+            let reason = run_session(&app, &ws_state, config, &client, stream).await;
+            log::warn!("WebSocket 会话结束：{}", reason);
+
+            let mut state = ws_state.lock().await;
+            state.client_id = None;
+            state.is_connected = false;
+            state.last_error = Some(reason);
+        }
+
+        let (attempt, last_error) = {
+            let mut state = ws_state.lock().await;
+            state.reconnect_attempts += 1;
+            (state.reconnect_attempts, state.last_error.clone())
+        };
+
+        if config.reconnect_max_attempts > 0 && attempt > config.reconnect_max_attempts {
+            log::error!("WebSocket 重连次数已达上限（{}），停止重连", config.reconnect_max_attempts);
+            // 先取出自身句柄，避免切换轮询时中止当前任务
+            ws_state.lock().await.abort_handle = None;
+            if app_config.transport.mode == TransportPreference::Auto {
+                transport::start_polling_transport(
+                    &app,
+                    &ws_state,
+                    client,
+                    app_config.transport.poll_interval_ms,
+                    last_error,
+                )
+                .await;
+            } else {
+                let _ = app.emit("ws_disconnected", ());
+            }
+            return;
+        }
+
+        let delay = backoff(
+            config.reconnect_initial_delay_ms,
+            config.reconnect_max_delay_ms,
+            attempt,
+        );
+        log::info!("WebSocket 将在 {}ms 后进行第 {} 次重连", delay.as_millis(), attempt);
+        let _ = app.emit(
+            "ws_reconnecting",
+            &WsReconnectingEvent {
+                attempt,
+                max_attempts: config.reconnect_max_attempts,
+                delay_ms: delay.as_millis() as u64,
+                last_error,
+            },
+        );
+
+        tokio::time::sleep(delay).await;
+
+        match connect_async_tls_with_config(&target.url, None, false, target.connector.clone()).await {
+            Ok((stream, _)) => {
+                log::info!("WebSocket 第 {} 次重连已建立连接", attempt);
+                ws_state.lock().await.is_connected = true;
+                next_stream = Some(stream);
+            }
+            Err(e) => {
+                log::error!("WebSocket 第 {} 次重连失败：{}", attempt, e);
+                ws_state.lock().await.last_error = Some(format!("WebSocket 连接失败：{}", e));
+            }
+        }
+    }
+}
+
+/// 计算第 attempt 次重连（重试）前的等待时间
+/// 基准值按 2 的幂增长并受上限约束，实际等待在 [基准/2, 基准] 之间随机取值
+pub(crate) fn backoff(initial_ms: u64, max_ms: u64, attempt: u32) -> Duration {
+    let exp = attempt.saturating_sub(1).min(20);
+    let base = initial_ms.saturating_mul(1u64 << exp).min(max_ms);
+    let half = base / 2;
+    Duration::from_millis(half + random_below(base - half + 1))
+}
+
+/// 生成 [0, bound) 范围内的随机数（仅用于退避抖动）
+pub(crate) fn random_below(bound: u64) -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    if bound == 0 {
+        return 0;
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.finish() % bound
+}
+
+/// 运行一次 WebSocket 会话：等待 client_id、发送心跳并转发消息
+/// 返回会话结束的原因
+async fn run_session(
+    app: &AppHandle,
+    ws_state: &WsConnectionStateRef,
+    config: &WebSocketConfig,
+    client: &ApiClient,
+    ws_stream: WsStream,
+) -> String {
+    let (mut write, mut read) = ws_stream.split();
+
+    // 等待接收 client_id
+    log::info!("等待 Python 后端发送 client_id...");
+    let client_id = match read.next().await {
+        Some(Ok(WsMessage::Text(text))) => {
+            log::info!("收到 WebSocket 文本消息：{}", &text[..std::cmp::min(text.len(), 200)]);
+            let msg: WsConnectedMessage = match serde_json::from_str(&text) {
+                Ok(m) => m,
+                Err(e) => {
+                    log::error!("原始消息：{}", text);
+                    return format!("解析连接消息失败：{}", e);
+                }
+            };
+            if msg.msg_type != "connected" {
+                return format!("未收到正确的连接确认消息，msg_type: {}", msg.msg_type);
+            }
+            msg.client_id
+        }
+        Some(Ok(msg)) => return format!("收到意外的 WebSocket 消息：{:?}", msg),
+        Some(Err(e)) => return format!("WebSocket 读取错误：{}", e),
+        None => return "WebSocket 连接意外关闭".to_string(),
+    };
+
+    // 收到 client_id 后更新状态，重连计数清零
+    let reconnected_after = {
+        let mut state = ws_state.lock().await;
+        state.client_id = Some(client_id.clone());
+        state.last_error = None;
+        std::mem::take(&mut state.reconnect_attempts)
+    };
+
+    log::info!("WebSocket 已连接，client_id: {}", client_id);
+    let _ = app.emit("ws_connected", &client_id);
+    if reconnected_after > 0 {
+        log::info!("WebSocket 经过 {} 次尝试后重连成功", reconnected_after);
+        let _ = app.emit(
+            "ws_reconnected",
+            &WsReconnectedEvent {
+                client_id: client_id.clone(),
+                attempts: reconnected_after,
+            },
+        );
+
+        // 断线期间的推送已丢失，且旧 client_id 上的任务不会再推送到新连接，
+        // 改为轮询这些任务的状态
+        let profile_id = app.state::<ProfileState>().active_profile().id;
+        let task_ids = app.state::<TaskRegistry>().active_task_ids(&profile_id);
+        if !task_ids.is_empty() {
+            log::info!("重连后恢复 {} 个未完成任务", task_ids.len());
+            let recovery = tokio::spawn(recover_in_flight_tasks(
+                app.clone(),
+                client.clone(),
+                task_ids,
+                Duration::from_millis(config.task_poll_interval_ms.max(100)),
+            ));
+            if let Some(old) = ws_state.lock().await.recovery_handle.replace(recovery) {
+                old.abort();
+            }
+        }
+    }
+
+    let mut heartbeat_interval =
+        tokio::time::interval(Duration::from_secs(config.heartbeat_interval_secs.max(1)));
+
+    loop {
+        tokio::select! {
+            _ = heartbeat_interval.tick() => {
+                if let Err(e) = write.send(WsMessage::Text("ping".to_string())).await {
+                    return format!("WebSocket 心跳发送失败：{}", e);
+                }
+            }
+            msg = read.next() => {
+                match msg {
+                    Some(Ok(WsMessage::Text(text))) => {
+                        if let Err(e) = handle_ws_message(app, &text) {
+                            log::error!("处理 WebSocket 消息失败：{}", e);
+                        }
+                    }
+                    Some(Ok(WsMessage::Close(frame))) => {
+                        return format!("WebSocket 连接被服务端关闭：{:?}", frame);
+                    }
+                    None => return "WebSocket 连接关闭".to_string(),
+                    Some(Err(e)) => return format!("WebSocket 错误：{}", e),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// 轮询重连前未完成的任务，直到全部结束
+///
+/// 认证失败时停止轮询；某个任务连续 [`MAX_RECOVERY_FAILURES`] 次查询失败时标记为失败。
+pub(crate) async fn recover_in_flight_tasks(
+    app: AppHandle,
+    client: ApiClient,
+    mut task_ids: Vec<String>,
+    poll_interval: Duration,
+) {
+    let mut failures: HashMap<String, u32> = HashMap::new();
+    loop {
+        let registry = app.state::<TaskRegistry>();
+        task_ids.retain(|id| registry.is_active(id));
+        if task_ids.is_empty() {
+            log::info!("断线期间的任务已全部恢复");
+            return;
+        }
+
+        for task_id in &task_ids {
+            match transport::poll_task(&app, &client, task_id).await {
+                Ok(()) => {
+                    failures.remove(task_id);
+                }
+                Err(e) if e.is_auth_error() => {
+                    log::error!("恢复任务状态时认证失败，停止轮询：{}", e);
+                    return;
+                }
+                Err(e) => {
+                    let count = failures.entry(task_id.clone()).or_default();
+                    *count += 1;
+                    if *count >= MAX_RECOVERY_FAILURES {
+                        log::error!("任务 {} 连续 {} 次查询状态失败，标记为失败", task_id, count);
+                        transport::fail_task(&app, task_id, &format!("无法获取任务状态：{}", e));
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+impl From<BackendDetectionResultItem> for DetectionResultItem {
+    fn from(value: BackendDetectionResultItem) -> Self {
+        DetectionResultItem {
+            mode: value.mode,
+            result: value.result,
+            confidence: value.confidence,
+            probabilities: value.probabilities,
+            processing_time: value.processing_time,
+            error: value.error,
+            image_index: value.image_index,
+        }
+    }
+}
+
+/// 处理收到的 WebSocket 消息并转发给前端
+fn handle_ws_message(app: &AppHandle, text: &str) -> Result<(), String> {
+    let value: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e| format!("解析 JSON 失败：{}", e))?;
+
+    let msg_type = value.get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    log::info!("收到 WebSocket 消息类型：{}, 完整消息：{}", msg_type, text);
+
+    let registry = app.state::<TaskRegistry>();
+
+    match msg_type {
+        "task_completed" | "task_partial_failure" => {
+            log::info!("收到 {} 消息", msg_type);
+            let data = value.get("data").ok_or("缺少 data 字段")?;
+            let task_id = data.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
+            log::info!("任务 ID: {}", task_id);
+            let status = data.get("status").and_then(|v| v.as_str()).unwrap_or("completed");
+            let message = data.get("message").and_then(|v| v.as_str());
+            let processed = data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32);
+            if !registry.finish(task_id, TaskStatus::from_backend(status), message, processed) {
+                log::info!("任务 {} 的结束事件已发送过，忽略", task_id);
+                return Ok(());
+            }
+
+            // 解析 completed_results（如果存在）
+            let completed_results = data.get("completed_results")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|item| {
+                            serde_json::from_value::<DetectionResultItem>(item.clone()).ok()
+                        })
+                        .collect::<Vec<_>>()
+                });
+
+            log::info!("解析 completed_results: {:?}", completed_results.as_ref().map(|r| r.len()));
+
+            let event = WsEventMessage {
+                event_type: "task_completed".to_string(),
+                task_id: task_id.to_string(),
+                status: Some(data.get("status").and_then(|v| v.as_str()).unwrap_or("completed").to_string()),
+                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                result: None,
+                total_items: data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32),
+                processed_items: data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32),
+                completed_results,
+            };
+
+            log::info!("发送 ws_task_completed 事件到前端：{}", event.task_id);
+            batch::emit_task_event(app, "ws_task_completed", event);
+        }
+        "task_failed" => {
+            let data = value.get("data").ok_or("缺少 data 字段")?;
+            let task_id = data.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
+            let message = data.get("message").and_then(|v| v.as_str());
+            if !registry.finish(task_id, TaskStatus::Failed, message, None) {
+                return Ok(());
+            }
+            let event = WsEventMessage {
+                event_type: "task_failed".to_string(),
+                task_id: task_id.to_string(),
+                status: Some("failed".to_string()),
+                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                result: None,
+                total_items: None,
+                processed_items: None,
+                completed_results: None,
+            };
+            log::error!("任务失败：{}", event.task_id);
+            batch::emit_task_event(app, "ws_task_failed", event);
+        }
+        "task_cancelled" => {
+            // 任务被取消
+            let data = value.get("data").ok_or("缺少 data 字段")?;
+            let task_id = data.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
+            let message = data.get("message").and_then(|v| v.as_str());
+            let processed = data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32);
+            if !registry.finish(task_id, TaskStatus::Cancelled, message, processed) {
+                return Ok(());
+            }
+            let event = WsEventMessage {
+                event_type: "task_completed".to_string(),
+                task_id: task_id.to_string(),
+                status: Some("cancelled".to_string()),
+                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                result: None,
+                total_items: data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32),
+                processed_items: data.get("processed_items").and_then(|v| v.as_u64()).map(|v| v as u32),
+                completed_results: None,
+            };
+            log::info!("任务已取消：{}", event.task_id);
+            batch::emit_task_event(app, "ws_task_completed", event);
+        }
+        "progress_update" | "progress" => {
+            log::info!("收到进度更新消息：{}", text);
+            let data = value.get("data").ok_or("缺少 data 字段")?;
+            let result = data.get("current_result").and_then(|r| r.as_object()).map(|r| DetectionResultItem {
+                mode: r.get("mode").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                result: r.get("result").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                confidence: r.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                probabilities: r.get("probabilities")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
+                    .unwrap_or_default(),
+                processing_time: r.get("processing_time").and_then(|v| v.as_u64()).unwrap_or(0),
+                error: r.get("error").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                image_index: r.get("image_index").and_then(|v| v.as_u64()).map(|v| v as u32),
+            });
+            log::info!("解析结果：{:?}", result.as_ref().map(|r| (&r.result, &r.error, &r.image_index)));
+
+            let task_id = data.get("task_id").and_then(|v| v.as_str()).unwrap_or("");
+            let total = data.get("total_items").and_then(|v| v.as_u64()).map(|v| v as u32);
+            let current = data.get("completed_items").and_then(|v| v.as_u64()).map(|v| v as u32);
+            let message = data.get("message").and_then(|v| v.as_str());
+            if !registry.record_progress(task_id, total, current, message, result.as_ref()) {
+                log::debug!("任务 {} 的结果已推送过，忽略", task_id);
+                return Ok(());
+            }
+
+            let progress = if let (Some(t), Some(c)) = (total, current) {
+                if t > 0 { (c as f64 / t as f64 * 100.0) as u32 } else { 0 }
+            } else {
+                0
+            };
+
+            let event = WsEventMessage {
+                event_type: "progress".to_string(),
+                task_id: task_id.to_string(),
+                status: Some("running".to_string()),
+                message: data.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                result,
+                total_items: total,
+                processed_items: current,
+                completed_results: None,
+            };
+            log::debug!("进度：{}% - {}", progress, event.message.as_deref().unwrap_or(""));
+            batch::emit_task_event(app, "ws_progress", event);
+        }
+        _ => {
+            log::debug!("收到未知类型消息：{}", msg_type);
+        }
+    }
+
+    Ok(())
+}
+
+/// 获取连接状态
+#[tauri::command]
+pub fn get_ws_status(
+    ws_state: State<'_, WsConnectionStateRef>,
+) -> Result<WsStatus, AppError> {
+    let state = ws_state.blocking_lock();
+    Ok(WsStatus {
+        client_id: state.client_id.clone(),
+        is_connected: state.is_connected,
+        reconnect_attempts: state.reconnect_attempts,
+        last_error: state.last_error.clone(),
+        transport: state.transport,
+    })
+}
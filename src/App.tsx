@@ -11,9 +11,8 @@ import { useDetection } from "./hooks/useDetection";
 import { useGlobalShortcuts } from "./hooks/useGlobalShortcuts";
 import { registerConnectionListener } from "./store/websocketManager";
 import { shortcutStore } from "./store/shortcutStore";
-import { retrieveApiKey } from "./api/tauri";
+import { hasApiKey } from "./api/tauri";
 import { theme } from "./main";
-import { detectionStore } from "./store";
 import "./css/App.css";
 
 function App(): React.ReactElement {
@@ -26,13 +25,7 @@ function App(): React.ReactElement {
   useEffect(() => {
     const checkApiKey = async () => {
       try {
-        const apiKey = await retrieveApiKey();
-        if (apiKey) {
-          setIsActivated(true);
-          detectionStore.setState({ api: apiKey });
-        } else {
-          setIsActivated(false);
-        }
+        setIsActivated(await hasApiKey());
       } catch (err) {
         console.error('[App] 读取 API Key 失败:', err);
         setIsActivated(false);
@@ -10,6 +10,14 @@ import type {
 
 import type {
   AsyncTaskResponse,
+  BatchJobInfo,
+  SingleModePathRequest,
+  FusionModePathRequest,
+  FolderDetectRequest,
+  FolderDetectResponse,
+  FolderScanResult,
+  ImageValidationReport,
+  PairingResult,
   ActivateRequest,
   ActivateResponse,
   HistoryQueryResponse,
@@ -18,28 +26,72 @@ import type {
   HistoryDeleteParams,
   ShortcutConfig,
   HistoryAllResponse,
+  HistoryTaskItem,
+  WsConnectionState,
+  StoredApiKeyInfo,
+  AdminSessionInfo,
+  ActivationCode,
+  ActivationCodeCreateParams,
+  ActivationCodeCreateResponse,
+  ActivationCodeListParams,
+  ActivationCodeListResponse,
+  ActivationCodeUpdateParams,
+  QueueStatus,
+  StoredImage,
+  StoredImageData,
+  StoredImageListParams,
+  StoredImageListResponse,
+  ImageUploadRequest,
 } from "./tauri";
 
 declare global {
   interface Window {
     __TAURI__?: {
       invoke: {
-        (cmd: "detect_single_mode_async", args: { request: SingleModeRequest; clientId: string; apiKey: string }): Promise<AsyncTaskResponse>;
-        (cmd: "detect_fusion_mode_async", args: { request: FusionModeRequest; clientId: string; apiKey: string }): Promise<AsyncTaskResponse>;
+        (cmd: "detect_single_mode_async", args: { request: SingleModeRequest; clientId: string }): Promise<AsyncTaskResponse>;
+        (cmd: "detect_fusion_mode_async", args: { request: FusionModeRequest; clientId: string }): Promise<AsyncTaskResponse>;
+        (cmd: "detect_single_mode_paths", args: { request: SingleModePathRequest; clientId: string }): Promise<AsyncTaskResponse>;
+        (cmd: "detect_fusion_mode_paths", args: { request: FusionModePathRequest; clientId: string }): Promise<AsyncTaskResponse>;
+        (cmd: "scan_image_folder", args: { folderPath: string; recursive?: boolean }): Promise<FolderScanResult>;
+        (cmd: "detect_folder", args: { request: FolderDetectRequest; clientId: string }): Promise<FolderDetectResponse>;
+        (cmd: "get_batch_job", args: { jobId: string }): Promise<BatchJobInfo>;
+        (cmd: "list_batch_jobs"): Promise<BatchJobInfo[]>;
+        (cmd: "pause_batch_job", args: { jobId: string }): Promise<BatchJobInfo>;
+        (cmd: "resume_batch_job", args: { jobId: string }): Promise<BatchJobInfo>;
+        (cmd: "cancel_batch_job", args: { jobId: string }): Promise<AsyncTaskResponse>;
+        (cmd: "pair_images", args: { paths: string[] }): Promise<PairingResult>;
+        (cmd: "pair_image_folder", args: { folderPath: string; recursive?: boolean }): Promise<PairingResult>;
         (cmd: "get_supported_formats"): Promise<string[]>;
-        (cmd: "validate_image", args: { imagePath: string }): Promise<boolean>;
-        (cmd: "connect_websocket", args: { apiKey: string }): Promise<string>;
-        (cmd: "get_ws_status"): Promise<[string | null, boolean]>;
+        (cmd: "validate_image", args: { imagePath: string }): Promise<ImageValidationReport>;
+        (cmd: "get_image_preview", args: { imagePath: string }): Promise<string>;
+        (cmd: "connect_websocket"): Promise<string>;
+        (cmd: "get_ws_status"): Promise<WsConnectionState>;
         (cmd: "activate_license", args: { request: ActivateRequest }): Promise<ActivateResponse>;
         (cmd: "query_history", args: { params: Record<string, string> }): Promise<HistoryQueryResponse>;
         (cmd: "get_all_history"): Promise<HistoryAllResponse>;
+        (cmd: "get_history_task", args: { taskId: string; forceRefresh?: boolean }): Promise<HistoryTaskItem>;
         (cmd: "get_history_stats", args: { params?: Record<string, string> }): Promise<HistoryStatsResponse>;
         (cmd: "delete_history", args: { params: HistoryDeleteParams }): Promise<HistoryDeleteResponse>;
+        (cmd: "upload_image", args: { request: ImageUploadRequest }): Promise<StoredImage>;
+        (cmd: "list_stored_images", args?: { params?: StoredImageListParams }): Promise<StoredImageListResponse>;
+        (cmd: "fetch_stored_image", args: { imageId: string }): Promise<StoredImageData>;
+        (cmd: "delete_stored_image", args: { imageId: string }): Promise<void>;
+        (cmd: "get_task_images", args: { taskId: string }): Promise<StoredImage[]>;
+        (cmd: "fetch_task_image", args: { taskId: string; imageIndex: number; modality?: string }): Promise<StoredImageData>;
         (cmd: "get_shortcuts_config"): Promise<ShortcutConfig>;
         (cmd: "save_shortcuts_config_command", args: { config: ShortcutConfig }): Promise<void>;
-        (cmd: "store_api_key", args: { apiKey: string }): Promise<void>;
-        (cmd: "retrieve_api_key"): Promise<string | null>;
-        (cmd: "delete_api_key"): Promise<void>;
+        (cmd: "store_api_key", args: { apiKey: string; profileId?: string }): Promise<void>;
+        (cmd: "has_api_key", args?: { profileId?: string }): Promise<boolean>;
+        (cmd: "delete_api_key", args?: { profileId?: string }): Promise<void>;
+        (cmd: "list_api_keys"): Promise<StoredApiKeyInfo[]>;
+        (cmd: "admin_login", args: { username: string; password: string }): Promise<AdminSessionInfo>;
+        (cmd: "admin_logout"): Promise<void>;
+        (cmd: "get_admin_session"): Promise<AdminSessionInfo | null>;
+        (cmd: "create_activation_codes", args: { params: ActivationCodeCreateParams }): Promise<ActivationCodeCreateResponse>;
+        (cmd: "list_activation_codes", args?: { params?: ActivationCodeListParams }): Promise<ActivationCodeListResponse>;
+        (cmd: "update_activation_code", args: { code: string; params: ActivationCodeUpdateParams }): Promise<ActivationCode>;
+        (cmd: "revoke_activation_code", args: { code: string }): Promise<void>;
+        (cmd: "get_queue_status"): Promise<QueueStatus>;
       };
     };
   }
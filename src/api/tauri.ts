@@ -5,6 +5,8 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import { open } from "@tauri-apps/plugin-dialog";
 import type { Event } from "@tauri-apps/api/event";
 import type {
   SingleModeRequest,
@@ -48,6 +50,60 @@ export interface WsEventMessage {
 export interface WsConnectionState {
   clientId: string | null;
   isConnected: boolean;
+  reconnectAttempts: number;  // 当前连续重连次数
+  lastError: string | null;   // 最近一次断线原因
+  transport: TransportMode;   // 当前推送方式
+}
+
+export type TransportMode = "websocket" | "polling";
+
+export interface TransportChangedEvent {
+  mode: TransportMode;
+  reason: string | null;  // 切换原因（如 WebSocket 连接失败信息）
+}
+
+export interface WsReconnectingEvent {
+  attempt: number;
+  maxAttempts: number;  // 0 表示不限次数
+  delayMs: number;
+  lastError: string | null;
+}
+
+export interface WsReconnectedEvent {
+  clientId: string;
+  attempts: number;
+}
+
+// ===== 错误类型 =====
+
+export type AppErrorCode =
+  | "network"
+  | "timeout"
+  | "unauthorized"
+  | "license_expired"
+  | "not_found"
+  | "bad_response"
+  | "validation"
+  | "config";
+
+/** Rust 命令返回的结构化错误 */
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  status: number | null;  // 后端返回的 HTTP 状态码
+  body: string | null;    // 后端返回的原始响应内容
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === "object" && err !== null && "code" in err && "message" in err;
+}
+
+/** 从命令错误中提取可展示的错误信息 */
+export function getErrorMessage(err: unknown, fallback: string): string {
+  if (isAppError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  if (typeof err === "string") return err;
+  return fallback;
 }
 
 // ===== 导出类型 =====
@@ -67,6 +123,16 @@ export interface TaskDetectionResultItem {
   imageIndex?: number;  // 图片在批次中的索引
 }
 
+// 图片检查结果（validate_image）
+export interface ImageValidationReport {
+  path: string;
+  format: "jpeg" | "png" | "bmp" | "webp";  // 按文件内容识别出的格式
+  extension: string;
+  width: number;
+  height: number;
+  bytes: number;
+}
+
 // ===== API 函数 =====
 
 export async function getSupportedFormats(): Promise<string[]> {
@@ -76,32 +142,89 @@ export async function getSupportedFormats(): Promise<string[]> {
   return await invoke<string[]>("get_supported_formats");
 }
 
-export async function validateImage(imagePath: string): Promise<boolean> {
+export async function validateImage(imagePath: string): Promise<ImageValidationReport> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<ImageValidationReport>("validate_image", { imagePath });
+}
+
+// ===== 本地图片选择 =====
+
+/**
+ * 通过系统文件对话框选择图片，返回本地路径（取消时返回空数组）
+ */
+export async function selectImageFiles(extensions: string[]): Promise<string[]> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  const selected = await open({
+    multiple: true,
+    directory: false,
+    filters: [{ name: "图片", extensions }],
+  });
+  if (!selected) {
+    return [];
+  }
+  return Array.isArray(selected) ? selected : [selected];
+}
+
+/**
+ * 生成本地图片的预览缩略图（JPEG data URL），原图不经过 IPC 传输
+ */
+export async function getImagePreview(imagePath: string): Promise<string> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<string>("get_image_preview", { imagePath });
+}
+
+/** 拖放到窗口上的本地文件（position 为相对窗口的 CSS 像素坐标） */
+export interface FileDropEvent {
+  paths: string[];
+  position: { x: number; y: number };
+}
+
+/**
+ * 监听拖放到窗口上的本地文件
+ */
+export async function listenFileDrop(callback: (event: FileDropEvent) => void): Promise<UnlistenFn> {
   if (!isTauri()) {
-    return true;
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
   }
-  return await invoke<boolean>("validate_image", { imagePath });
+  return await getCurrentWebview().onDragDropEvent((event) => {
+    if (event.payload.type !== "drop") {
+      return;
+    }
+    const scale = window.devicePixelRatio || 1;
+    callback({
+      paths: event.payload.paths,
+      position: {
+        x: event.payload.position.x / scale,
+        y: event.payload.position.y / scale,
+      },
+    });
+  });
 }
 
-export async function connectWebsocket(apiKey: string): Promise<string> {
+export async function connectWebsocket(): Promise<string> {
   if (!isTauri()) {
     throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
   }
-  return await invoke<string>("connect_websocket", { apiKey });
+  return await invoke<string>("connect_websocket");
 }
 
 export async function getWsStatus(): Promise<WsConnectionState> {
   if (!isTauri()) {
-    return { clientId: null, isConnected: false };
+    return { clientId: null, isConnected: false, reconnectAttempts: 0, lastError: null, transport: "websocket" };
   }
-  const result = await invoke<[string | null, boolean]>("get_ws_status");
-  return { clientId: result[0], isConnected: result[1] };
+  return await invoke<WsConnectionState>("get_ws_status");
 }
 
+// Base64 提交的任务不重试检测失败的图片，需要重试时使用 detectSingleModePaths
 export async function detectSingleModeAsync(
   request: SingleModeRequest,
-  clientId: string,
-  apiKey: string
+  clientId: string
 ): Promise<AsyncTaskResponse> {
   if (!isTauri()) {
     throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
@@ -109,14 +232,13 @@ export async function detectSingleModeAsync(
   return await invoke<AsyncTaskResponse>("detect_single_mode_async", {
     request,
     clientId,
-    apiKey,
   });
 }
 
+// Base64 提交的任务不重试检测失败的图片对，需要重试时使用 detectFusionModePaths
 export async function detectFusionModeAsync(
   request: FusionModeRequest,
-  clientId: string,
-  apiKey: string
+  clientId: string
 ): Promise<AsyncTaskResponse> {
   if (!isTauri()) {
     throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
@@ -124,15 +246,374 @@ export async function detectFusionModeAsync(
   return await invoke<AsyncTaskResponse>("detect_fusion_mode_async", {
     request,
     clientId,
-    apiKey,
   });
 }
 
-export async function cancelDetection(taskId: string, apiKey: string): Promise<AsyncTaskResponse> {
+/** 单模态检测请求（图片路径，由 Rust 端读取、检查并编码） */
+export interface SingleModePathRequest {
+  mode: "single";
+  modality: string;
+  imagePaths: string[];
+}
+
+/** 融合模式检测请求（图片路径对） */
+export interface FusionModePathRequest {
+  mode: "fusion";
+  pairs: Array<{ rgb: string; ir: string }>;
+}
+
+/**
+ * 按文件路径提交单模态检测，图片不经过 IPC 传输
+ */
+export async function detectSingleModePaths(
+  request: SingleModePathRequest,
+  clientId: string
+): Promise<AsyncTaskResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<AsyncTaskResponse>("detect_single_mode_paths", {
+    request,
+    clientId,
+  });
+}
+
+/**
+ * 按文件路径提交融合模式检测
+ */
+export async function detectFusionModePaths(
+  request: FusionModePathRequest,
+  clientId: string
+): Promise<AsyncTaskResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<AsyncTaskResponse>("detect_fusion_mode_paths", {
+    request,
+    clientId,
+  });
+}
+
+/** 文件夹检测请求 */
+export interface FolderDetectRequest {
+  folderPath: string;
+  mode: "single";
+  modality: string;
+  recursive?: boolean;  // 是否扫描子目录，默认 true
+}
+
+/** 被跳过的文件及原因 */
+export interface SkippedImage {
+  path: string;
+  reason: string;
+}
+
+/** 文件夹扫描结果 */
+export interface FolderScanResult {
+  folderPath: string;
+  images: ImageValidationReport[];
+  skipped: SkippedImage[];
+  totalBytes: number;
+}
+
+/** 文件夹批量检测结果 */
+export interface FolderDetectResponse {
+  task: AsyncTaskResponse;  // 图片较多时为批量任务
+  submitted: number;
+  skipped: SkippedImage[];
+}
+
+/**
+ * 扫描文件夹中可检测的图片（不提交）
+ */
+export async function scanImageFolder(
+  folderPath: string,
+  recursive = true
+): Promise<FolderScanResult> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<FolderScanResult>("scan_image_folder", { folderPath, recursive });
+}
+
+/**
+ * 文件夹批量检测，图片较多时拆分为多个子任务提交
+ */
+export async function detectFolder(
+  request: FolderDetectRequest,
+  clientId: string
+): Promise<FolderDetectResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<FolderDetectResponse>("detect_folder", {
+    request,
+    clientId,
+  });
+}
+
+// ===== 批量任务 =====
+
+type BatchTaskStatus = "pending" | "running" | "completed" | "partial_failure" | "failed" | "cancelled";
+
+/** 批量任务的子任务 */
+export interface BatchChunk {
+  offset: number;  // 第一张图片在整批中的索引
+  totalItems: number;
+  processedItems: number;
+  taskId: string | null;
+  status: BatchTaskStatus;  // pending 表示尚未提交
+  error: string | null;
+}
+
+/** 批量任务（图片数量超过 batch.max_images_per_task 时自动拆分） */
+export interface BatchJobInfo {
+  jobId: string;
+  mode: string;
+  modality: string | null;
+  profileId: string;  // 提交时的服务器配置，切换到其他服务器时暂不提交
+  totalItems: number;
+  processedItems: number;
+  status: BatchTaskStatus;
+  paused: boolean;
+  persistent: boolean;  // 按路径提交的任务保存到磁盘，重启后自动继续
+  chunks: BatchChunk[];
+  submittedAt: number;
+  finishedAt: number | null;
+}
+
+/**
+ * 获取批量任务及其子任务的状态
+ */
+export async function getBatchJob(jobId: string): Promise<BatchJobInfo> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BatchJobInfo>("get_batch_job", { jobId });
+}
+
+/**
+ * 列出批量任务（包括启动时恢复的任务）
+ */
+export async function listBatchJobs(): Promise<BatchJobInfo[]> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BatchJobInfo[]>("list_batch_jobs");
+}
+
+/**
+ * 暂停批量任务：不再提交新的子任务，已提交的子任务继续运行
+ */
+export async function pauseBatchJob(jobId: string): Promise<BatchJobInfo> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BatchJobInfo>("pause_batch_job", { jobId });
+}
+
+/**
+ * 继续已暂停的批量任务
+ */
+export async function resumeBatchJob(jobId: string): Promise<BatchJobInfo> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BatchJobInfo>("resume_batch_job", { jobId });
+}
+
+/**
+ * 取消批量任务
+ */
+export async function cancelBatchJob(jobId: string): Promise<AsyncTaskResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<AsyncTaskResponse>("cancel_batch_job", { jobId });
+}
+
+/**
+ * 监听批量任务状态变化（提交、子任务结束、暂停/继续、取消、启动时恢复）
+ */
+export async function listenBatchJobUpdated(callback: (job: BatchJobInfo) => void): Promise<UnlistenFn> {
+  return await listen<BatchJobInfo>("batch_job_updated", (event: Event<BatchJobInfo>) => {
+    callback(event.payload);
+  });
+}
+
+// ===== RGB/IR 配对 =====
+
+/** 配对成功的图片 */
+export interface MatchedPair {
+  key: string;  // 配对标识（所在目录 + 去掉模态标记后的文件名）
+  rgb: string;
+  ir: string;
+}
+
+/** 未能配对的图片 */
+export interface PairingOrphan {
+  path: string;
+  modality: "rgb" | "ir" | null;  // 无法识别模态时为 null
+  reason: string;
+}
+
+/** 同一配对标识下有多张 RGB 或多张 IR 图片 */
+export interface PairingAmbiguity {
+  key: string;
+  rgb: string[];
+  ir: string[];
+}
+
+/** 配对结果 */
+export interface PairingResult {
+  pairs: MatchedPair[];
+  orphans: PairingOrphan[];
+  ambiguities: PairingAmbiguity[];
+  skipped: SkippedImage[];  // 扫描文件夹时未通过检查的文件
+}
+
+/**
+ * 按配置的规则（前缀、后缀、目录、正则）对图片路径或文件名配对
+ */
+export async function pairImages(paths: string[]): Promise<PairingResult> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<PairingResult>("pair_images", { paths });
+}
+
+/**
+ * 扫描文件夹并配对其中的图片
+ */
+export async function pairImageFolder(
+  folderPath: string,
+  recursive = true
+): Promise<PairingResult> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<PairingResult>("pair_image_folder", { folderPath, recursive });
+}
+
+export async function cancelDetection(taskId: string): Promise<AsyncTaskResponse> {
   if (!isTauri()) {
     return { task_id: taskId, message: "任务已取消（模拟）" };
   }
-  return await invoke<AsyncTaskResponse>("cancel_detection", { taskId, apiKey });
+  return await invoke<AsyncTaskResponse>("cancel_detection", { taskId });
+}
+
+export interface TaskStatusResponse {
+  taskId: string;
+  status: string;  // "pending" | "running" | "completed" | "partial_failure" | "failed" | "cancelled"
+  totalItems: number;
+  processedItems: number;
+  message: string | null;
+  results: RustTaskDetectionResultItem[];
+}
+
+export interface TaskSummary {
+  taskId: string;
+  mode: string | null;
+  status: string;
+  totalItems: number;
+  processedItems: number;
+  clientId: string | null;
+  createdAt: string | null;
+}
+
+export interface TaskListResponse {
+  total: number;
+  tasks: TaskSummary[];
+}
+
+/**
+ * 查询任务状态（WebSocket 不可用时的轮询方案）
+ */
+export async function getTaskStatus(taskId: string): Promise<TaskStatusResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<TaskStatusResponse>("get_task_status", { taskId });
+}
+
+/**
+ * 列出当前 API Key 下的活跃任务
+ */
+export async function listActiveTasks(): Promise<TaskListResponse> {
+  if (!isTauri()) {
+    return { total: 0, tasks: [] };
+  }
+  return await invoke<TaskListResponse>("list_active_tasks");
+}
+
+// ===== 任务注册表 =====
+
+export type TaskStatus =
+  | "pending"
+  | "running"
+  | "completed"
+  | "partial_failure"
+  | "failed"
+  | "cancelled";
+
+export interface TaskRecord {
+  taskId: string;
+  mode: string;               // "single" 或 "fusion"
+  profileId: string;          // 提交任务的服务器配置
+  modality: string | null;
+  submittedAt: number;        // Unix 毫秒
+  totalItems: number;
+  processedItems: number;
+  status: TaskStatus;
+  message: string | null;
+  results: RustTaskDetectionResultItem[];
+  finishedAt: number | null;  // Unix 毫秒
+  retryOf: string | null;     // 重试失败图片的后续任务：最初提交的任务 ID
+  retryAttempt: number;       // 重试次数（原任务为 0）
+}
+
+/** 失败图片重新提交的后续任务（task_retry_submitted 事件） */
+export interface RetrySubmittedEvent {
+  taskId: string;             // 后续任务 ID（可能是批量任务 ID）
+  originalTaskId: string;     // 最初提交的任务 ID
+  parentTaskId: string;       // 上一次提交的任务 ID
+  attempt: number;            // 第几次重试（从 1 开始）
+  imageIndices: number[];     // 后续任务中每张图片在原任务中的索引
+}
+
+/**
+ * 监听失败图片的重试任务提交，后续任务推送中的 imageIndex 需经 imageIndices 映射回原任务
+ */
+export async function listenTaskRetrySubmitted(
+  callback: (event: RetrySubmittedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<RetrySubmittedEvent>("task_retry_submitted", (event: Event<RetrySubmittedEvent>) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 列出本客户端提交的所有任务（用于刷新页面后恢复状态）
+ */
+export async function listTasks(): Promise<TaskRecord[]> {
+  if (!isTauri()) {
+    return [];
+  }
+  return await invoke<TaskRecord[]>("list_tasks");
+}
+
+export async function getTask(taskId: string): Promise<TaskRecord> {
+  return await invoke<TaskRecord>("get_task", { taskId });
+}
+
+/**
+ * 清除任务记录，默认只清除已结束的任务
+ */
+export async function clearTasks(includeActive = false): Promise<number> {
+  if (!isTauri()) {
+    return 0;
+  }
+  return await invoke<number>("clear_tasks", { includeActive });
 }
 
 // ===== WebSocket 事件监听 =====
@@ -181,27 +662,188 @@ export async function listenWsDisconnected(callback: () => void): Promise<Unlist
   });
 }
 
+export async function listenWsReconnecting(
+  callback: (event: WsReconnectingEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<WsReconnectingEvent>("ws_reconnecting", (event: Event<WsReconnectingEvent>) => {
+    callback(event.payload);
+  });
+}
+
+export async function listenWsReconnected(
+  callback: (event: WsReconnectedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<WsReconnectedEvent>("ws_reconnected", (event: Event<WsReconnectedEvent>) => {
+    callback(event.payload);
+  });
+}
+
+export async function listenTransportChanged(
+  callback: (event: TransportChangedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<TransportChangedEvent>("transport_changed", (event: Event<TransportChangedEvent>) => {
+    callback(event.payload);
+  });
+}
+
+// ===== 后端健康检查 =====
+
+export type BackendHealthState = "unknown" | "up" | "degraded" | "down";
+
+export interface BackendHealth {
+  state: BackendHealthState;
+  baseUrl: string;
+  latencyMs: number | null;       // 最近一次检查的响应延迟
+  message: string | null;         // 降级或不可用的原因
+  backendVersion: string | null;
+  checkedAt: number | null;       // Unix 毫秒
+  changedAt: number | null;       // 状态最近一次变化时间
+}
+
+export async function getBackendHealth(): Promise<BackendHealth> {
+  if (!isTauri()) {
+    return {
+      state: "unknown",
+      baseUrl: "",
+      latencyMs: null,
+      message: null,
+      backendVersion: null,
+      checkedAt: null,
+      changedAt: null,
+    };
+  }
+  return await invoke<BackendHealth>("get_backend_health");
+}
+
+export async function listenBackendHealth(
+  callback: (health: BackendHealth) => void
+): Promise<UnlistenFn> {
+  return await listen<BackendHealth>("backend_health", (event: Event<BackendHealth>) => {
+    callback(event.payload);
+  });
+}
+
+// ===== 服务器配置 =====
+
+export interface TlsSettings {
+  acceptInvalidCerts: boolean;  // 跳过证书校验（仅用于测试环境）
+  caCertPath: string | null;    // 额外信任的 CA 证书（PEM 文件路径）
+}
+
+export interface BackendProfile {
+  id: string;
+  name: string;
+  baseUrl: string;
+  requestTimeoutSecs: number;
+  connectTimeoutSecs: number;
+  tls: TlsSettings;
+}
+
+export interface ProfilesConfig {
+  activeProfileId: string;
+  profiles: BackendProfile[];
+}
+
+export interface ProfileInput {
+  name: string;
+  baseUrl: string;
+  requestTimeoutSecs?: number;
+  connectTimeoutSecs?: number;
+  tls?: TlsSettings;
+}
+
+export async function listProfiles(): Promise<ProfilesConfig> {
+  if (!isTauri()) {
+    return { activeProfileId: "", profiles: [] };
+  }
+  return await invoke<ProfilesConfig>("list_profiles");
+}
+
+export async function addProfile(profile: ProfileInput): Promise<BackendProfile> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BackendProfile>("add_profile", { profile });
+}
+
+/**
+ * 编辑服务器配置；编辑当前服务器时会重新建立 WebSocket 连接
+ * 修改服务器地址时会清除该配置的 API Key 和授权信息，需要重新激活
+ */
+export async function updateProfile(profileId: string, profile: ProfileInput): Promise<BackendProfile> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BackendProfile>("update_profile", { profileId, profile });
+}
+
+export async function deleteProfile(profileId: string): Promise<void> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  await invoke("delete_profile", { profileId });
+}
+
+/**
+ * 切换当前服务器，断开旧连接；新服务器已激活时自动连接其 WebSocket
+ */
+export async function switchProfile(profileId: string): Promise<BackendProfile> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<BackendProfile>("switch_profile", { profileId });
+}
+
+export async function listenProfileChanged(
+  callback: (profile: BackendProfile) => void
+): Promise<UnlistenFn> {
+  return await listen<BackendProfile>("profile_changed", (event: Event<BackendProfile>) => {
+    callback(event.payload);
+  });
+}
+
 // ===== 激活码验证 =====
 
 export interface ActivateRequest {
   activationCode: string;
+  licenseFile?: string;  // 离线授权文件内容，提供时在本地验证签名，不请求后端
 }
 
+/** 激活码本地格式校验失败的原因 */
+export type ActivationCodeRejection =
+  | { reason: "empty" }
+  | { reason: "missing_prefix" }
+  | { reason: "invalid_character"; character: string; position: number }
+  | { reason: "too_short"; length: number }
+  | { reason: "too_long"; length: number }
+  | { reason: "malformed_groups" };
+
+/** 离线授权文件验证失败的原因 */
+export type LicenseFileRejection =
+  | { reason: "invalid_format"; detail: string }
+  | { reason: "no_trusted_key" }
+  | { reason: "invalid_signature" }
+  | { reason: "unsupported_version"; version: number }
+  | { reason: "missing_api_key" }
+  | { reason: "invalid_expiry"; expiresAt: string }
+  | { reason: "expired"; expiresAt: string }
+  | { reason: "server_mismatch"; licensed: string; current: string };
+
 export interface ActivateResponse {
   success: boolean;
   message: string;
-  apiKey?: string;
-  expiresAt?: string;
+  expiresAt?: string;  // API Key 由 Rust 端直接保存，不返回给前端
+  rejection?: ActivationCodeRejection | LicenseFileRejection | null;  // 本地校验未通过时返回，不会请求后端
 }
 
 export async function activateLicense(request: ActivateRequest): Promise<ActivateResponse> {
   if (!isTauri()) {
     // 开发模式：模拟验证
     await new Promise((resolve) => setTimeout(resolve, 1000));
+    localStorage.setItem("api_key", "sk_dev_mock_api_key_" + Date.now());
     return {
       success: true,
       message: "激活成功",
-      apiKey: "sk_dev_mock_api_key_" + Date.now(),
     };
   }
   return await invoke<ActivateResponse>("activate_license", {
@@ -209,6 +851,185 @@ export async function activateLicense(request: ActivateRequest): Promise<Activat
   });
 }
 
+// ===== 授权状态 =====
+
+export type LicenseState = "unlicensed" | "active" | "expiring_soon" | "expired" | "revoked";
+
+export interface LicenseStatus {
+  state: LicenseState;
+  profileId: string;
+  expiresAt: string | null;
+  remainingSecs: number | null;  // 长期有效或未激活时为空
+  remainingDays: number | null;
+  message: string | null;
+}
+
+export interface LicenseExpiryWarning {
+  profileId: string;
+  daysBefore: number;   // 触发的提醒档位（到期前天数）
+  expiresAt: string;
+  remainingSecs: number;
+}
+
+export async function getLicenseStatus(): Promise<LicenseStatus> {
+  if (!isTauri()) {
+    return {
+      state: localStorage.getItem("api_key") ? "active" : "unlicensed",
+      profileId: "",
+      expiresAt: null,
+      remainingSecs: null,
+      remainingDays: null,
+      message: null,
+    };
+  }
+  return await invoke<LicenseStatus>("get_license_status");
+}
+
+export async function listenLicenseStateChanged(
+  callback: (status: LicenseStatus) => void
+): Promise<UnlistenFn> {
+  return await listen<LicenseStatus>("license_state_changed", (event: Event<LicenseStatus>) => {
+    callback(event.payload);
+  });
+}
+
+export async function listenLicenseExpiryWarning(
+  callback: (warning: LicenseExpiryWarning) => void
+): Promise<UnlistenFn> {
+  return await listen<LicenseExpiryWarning>("license_expiry_warning", (event: Event<LicenseExpiryWarning>) => {
+    callback(event.payload);
+  });
+}
+
+// ===== 管理员（JWT） =====
+
+export interface AdminSessionInfo {
+  username: string;
+  profileId: string;
+  expiresAt: number | null;     // Unix 毫秒，后端未提供有效期时为空
+  remainingSecs: number | null;
+}
+
+export interface ActivationCode {
+  code: string;
+  status: string;  // unused / used / expired / revoked
+  note: string | null;
+  max_uses: number | null;
+  used_count: number;
+  created_at: string | null;
+  expires_at: string | null;
+  used_at: string | null;
+}
+
+export interface ActivationCodeCreateParams {
+  count?: number;       // 生成数量，默认 1
+  validDays?: number;   // 激活后的有效天数
+  expiresAt?: string;   // 激活码本身的过期时间
+  maxUses?: number;
+  note?: string;
+}
+
+export interface ActivationCodeUpdateParams {
+  expiresAt?: string;
+  maxUses?: number;
+  note?: string;
+}
+
+export interface ActivationCodeListParams {
+  status?: string;
+  page?: number;
+  pageSize?: number;
+}
+
+export interface ActivationCodeListResponse {
+  total: number;
+  items: ActivationCode[];
+}
+
+export interface ActivationCodeCreateResponse {
+  codes: ActivationCode[];
+  message: string | null;
+}
+
+export interface QueueStatus {
+  pending: number;
+  processing: number;
+  max_workers: number | null;
+  [key: string]: unknown;  // 后端返回的其他字段
+}
+
+/**
+ * 管理员登录，JWT 只保存在 Rust 端内存中，切换服务器或过期后需重新登录
+ */
+export async function adminLogin(username: string, password: string): Promise<AdminSessionInfo> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<AdminSessionInfo>("admin_login", { username, password });
+}
+
+export async function adminLogout(): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  await invoke("admin_logout");
+}
+
+export async function getAdminSession(): Promise<AdminSessionInfo | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<AdminSessionInfo | null>("get_admin_session");
+}
+
+export async function listenAdminSessionExpired(callback: () => void): Promise<UnlistenFn> {
+  return await listen("admin_session_expired", () => {
+    callback();
+  });
+}
+
+export async function createActivationCodes(
+  params: ActivationCodeCreateParams
+): Promise<ActivationCodeCreateResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<ActivationCodeCreateResponse>("create_activation_codes", { params });
+}
+
+export async function listActivationCodes(
+  params?: ActivationCodeListParams
+): Promise<ActivationCodeListResponse> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<ActivationCodeListResponse>("list_activation_codes", { params });
+}
+
+export async function updateActivationCode(
+  code: string,
+  params: ActivationCodeUpdateParams
+): Promise<ActivationCode> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<ActivationCode>("update_activation_code", { code, params });
+}
+
+export async function revokeActivationCode(code: string): Promise<void> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  await invoke("revoke_activation_code", { code });
+}
+
+export async function getQueueStatus(): Promise<QueueStatus> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<QueueStatus>("get_queue_status");
+}
+
 // ===== 历史记录 API =====
 
 export interface HistoryResultItem {
@@ -293,13 +1114,6 @@ export interface HistoryAllResponse {
   items: HistoryTaskItem[];
 }
 
-/**
- * 获取存储的 API Key
- */
-async function getApiKey(): Promise<string> {
-  return await retrieveApiKey() || "";
-}
-
 /**
  * 查询历史记录
  */
@@ -340,11 +1154,9 @@ export async function queryHistory(params: HistoryQueryParams): Promise<HistoryQ
   if (params.page) queryParams.page = params.page.toString();
   if (params.pageSize) queryParams.pageSize = params.pageSize.toString();
 
-  const apiKey = await getApiKey();
-  console.log("[History API] queryHistory 调用 Rust，params:", queryParams, "apiKey:", apiKey ? "存在" : "空");
+  console.log("[History API] queryHistory 调用 Rust，params:", queryParams);
   const result = await invoke<HistoryQueryResponse>("query_history", {
     params: queryParams,
-    apiKey,
   });
   console.log("[History API] queryHistory 返回结果:", result);
   return result;
@@ -378,11 +1190,9 @@ export async function getHistoryStats(params?: HistoryStatsParams): Promise<Hist
   if (params?.status) queryParams.status = params.status;
   if (params?.days) queryParams.days = params.days.toString();
 
-  const apiKey = await getApiKey();
-  console.log("[History API] getHistoryStats 调用 Rust，params:", queryParams, "apiKey:", apiKey ? "存在" : "空");
+  console.log("[History API] getHistoryStats 调用 Rust，params:", queryParams);
   return await invoke<HistoryStatsResponse>("get_history_stats", {
     params: queryParams,
-    apiKey,
   });
 }
 
@@ -396,10 +1206,8 @@ export async function deleteHistory(params: HistoryDeleteParams): Promise<Histor
     return { success: true, message: "删除成功（模拟）" };
   }
 
-  const apiKey = await getApiKey();
   return await invoke<HistoryDeleteResponse>("delete_history", {
     params,
-    apiKey,
   });
 }
 
@@ -417,14 +1225,111 @@ export async function getAllHistory(): Promise<HistoryAllResponse> {
     };
   }
 
-  const apiKey = await getApiKey();
-  console.log("[History API] getAllHistory 调用，apiKey:", apiKey ? apiKey.substring(0, 12) + "..." : "空");
-  if (!apiKey) {
-    console.error("[History API] API Key 为空，请先激活产品");
+  console.log("[History API] getAllHistory 调用");
+  return await invoke<HistoryAllResponse>("get_all_history");
+}
+
+/**
+ * 获取单个历史任务的完整详情（包含每张图片的结果）
+ * 已结束的任务由 Rust 端缓存，forceRefresh 为 true 时重新请求
+ */
+export async function getHistoryTask(taskId: string, forceRefresh = false): Promise<HistoryTaskItem> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
   }
-  return await invoke<HistoryAllResponse>("get_all_history", {
-    apiKey,
-  });
+  return await invoke<HistoryTaskItem>("get_history_task", { taskId, forceRefresh });
+}
+
+// ===== 图片存储 =====
+
+export interface StoredImage {
+  image_id: string;
+  task_id: string | null;
+  image_index: number | null;  // 与历史结果 HistoryResultItem.imageIndex 对应
+  modality: string | null;     // 融合模式下为 rgb / ir
+  filename: string | null;
+  content_type: string | null;
+  size: number;
+  created_at: string | null;
+}
+
+export interface StoredImageListResponse {
+  total: number;
+  items: StoredImage[];
+}
+
+export interface StoredImageListParams {
+  taskId?: string;
+  page?: number;
+  pageSize?: number;
+}
+
+export interface ImageUploadRequest {
+  imageData: string;  // Base64（可带 data URL 前缀）
+  filename?: string;
+  taskId?: string;
+  imageIndex?: number;
+  modality?: string;
+}
+
+export interface StoredImageData {
+  imageId: string;
+  contentType: string;
+  size: number;
+  dataUrl: string;     // 可直接用于 <img src>
+  fromCache: boolean;  // 是否命中本地缓存
+}
+
+export async function uploadImage(request: ImageUploadRequest): Promise<StoredImage> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<StoredImage>("upload_image", { request });
+}
+
+export async function listStoredImages(params?: StoredImageListParams): Promise<StoredImageListResponse> {
+  if (!isTauri()) {
+    return { total: 0, items: [] };
+  }
+  return await invoke<StoredImageListResponse>("list_stored_images", { params });
+}
+
+/**
+ * 获取图片内容，已下载的图片从本地磁盘缓存读取
+ */
+export async function fetchStoredImage(imageId: string): Promise<StoredImageData> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<StoredImageData>("fetch_stored_image", { imageId });
+}
+
+export async function deleteStoredImage(imageId: string): Promise<void> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  await invoke("delete_stored_image", { imageId });
+}
+
+export async function getTaskImages(taskId: string): Promise<StoredImage[]> {
+  if (!isTauri()) {
+    return [];
+  }
+  return await invoke<StoredImage[]>("get_task_images", { taskId });
+}
+
+/**
+ * 根据历史结果的 imageIndex（融合模式下还需 modality）获取任务中对应的图片
+ */
+export async function fetchTaskImage(
+  taskId: string,
+  imageIndex: number,
+  modality?: string
+): Promise<StoredImageData> {
+  if (!isTauri()) {
+    throw new Error("请使用 Tauri 模式运行: npm run tauri dev");
+  }
+  return await invoke<StoredImageData>("fetch_task_image", { taskId, imageIndex, modality });
 }
 
 // ===== 快捷键配置 API =====
@@ -472,9 +1377,9 @@ export async function saveShortcutsConfig(config: ShortcutConfig): Promise<void>
 // ===== API Key 安全存储（系统密钥环） =====
 
 /**
- * 将 API Key 存储到系统密钥环
+ * 将 API Key 存储到系统密钥环（未指定 profileId 时存到当前服务器配置下）
  */
-export async function storeApiKey(apiKey: string): Promise<void> {
+export async function storeApiKey(apiKey: string, profileId?: string): Promise<void> {
   console.log("[Keyring API] 存储 API Key 到系统密钥环");
   if (!isTauri()) {
     console.log("[Keyring API] 开发模式，模拟存储到 localStorage");
@@ -482,26 +1387,26 @@ export async function storeApiKey(apiKey: string): Promise<void> {
     await new Promise((resolve) => setTimeout(resolve, 300));
     return;
   }
-  await invoke<void>("store_api_key", { apiKey });
+  await invoke<void>("store_api_key", { apiKey, profileId });
   console.log("[Keyring API] API Key 已存储到系统密钥环");
 }
 
 /**
- * 从系统密钥环检索 API Key
+ * 检查是否已保存 API Key（未指定 profileId 时检查当前服务器配置）
+ * API Key 只保存在 Rust 端，前端无法读取密钥本身
  */
-export async function retrieveApiKey(): Promise<string | null> {
-  console.log("[Keyring API] 从系统密钥环检索 API Key");
+export async function hasApiKey(profileId?: string): Promise<boolean> {
   if (!isTauri()) {
     console.log("[Keyring API] 开发模式，从 localStorage 读取");
-    return localStorage.getItem("api_key");
+    return localStorage.getItem("api_key") !== null;
   }
-  return await invoke<string | null>("retrieve_api_key");
+  return await invoke<boolean>("has_api_key", { profileId });
 }
 
 /**
- * 从系统密钥环删除 API Key
+ * 从系统密钥环删除 API Key（未指定 profileId 时删除当前服务器配置）
  */
-export async function deleteApiKey(): Promise<void> {
+export async function deleteApiKey(profileId?: string): Promise<void> {
   console.log("[Keyring API] 从系统密钥环删除 API Key");
   if (!isTauri()) {
     console.log("[Keyring API] 开发模式，从 localStorage 删除");
@@ -509,6 +1414,24 @@ export async function deleteApiKey(): Promise<void> {
     await new Promise((resolve) => setTimeout(resolve, 300));
     return;
   }
-  await invoke<void>("delete_api_key");
+  await invoke<void>("delete_api_key", { profileId });
   console.log("[Keyring API] API Key 已从密钥环删除");
+}
+
+export interface StoredApiKeyInfo {
+  profileId: string;
+  profileName: string;
+  baseUrl: string;
+  hasApiKey: boolean;
+  isActive: boolean;
+}
+
+/**
+ * 列出各服务器配置的 API Key 存储情况（不返回密钥本身）
+ */
+export async function listApiKeys(): Promise<StoredApiKeyInfo[]> {
+  if (!isTauri()) {
+    return [];
+  }
+  return await invoke<StoredApiKeyInfo[]>("list_api_keys");
 }
\ No newline at end of file
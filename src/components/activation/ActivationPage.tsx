@@ -1,11 +1,10 @@
-import { useState, useCallback } from "react";
+import { useState, useCallback, useRef } from "react";
 import {
   activateLicense,
   type ActivateRequest,
-  storeApiKey
+  getErrorMessage,
 } from "../../api/tauri";
 import { TitleBar } from "../layout/TitleBar";
-import { detectionStore } from "../../store";
 import "../../css/Activation.css";
 
 interface ActivationPageProps {
@@ -62,9 +61,9 @@ export function ActivationPage({ onActivate }: ActivationPageProps): React.React
   const handleSubmit = useCallback(async (e: React.FormEvent) => {
     e.preventDefault();
 
-    // 验证激活码格式：只需以 ACT- 开头且长度大于 4
-    if (!activationCode.startsWith("ACT-") || activationCode.length <= 4) {
-      setError("激活码格式不正确");
+    // 完整的格式校验由 Rust 端完成，这里只拦截空输入
+    if (!activationCode.trim()) {
+      setError("请输入激活码");
       setIsValid(false);
       return;
     }
@@ -79,12 +78,9 @@ export function ActivationPage({ onActivate }: ActivationPageProps): React.React
       const response = await activateLicense(request);
       setIsValidating(false);
 
-      if (response.success && response.apiKey) {
+      if (response.success) {
         setIsValid(true);
-        // 存储 API Key 到系统密钥环
-        await storeApiKey(response.apiKey);
-        // 同时存储到 detectionStore
-        detectionStore.setState({ api: response.apiKey });
+        // API Key 已由 Rust 端保存到系统密钥环
         // 等待 2.5 秒后进入主页面
         setTimeout(() => onActivate?.(), 2500);
       } else {
@@ -94,10 +90,41 @@ export function ActivationPage({ onActivate }: ActivationPageProps): React.React
     } catch (err) {
       setIsValidating(false);
       setIsValid(false);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err, "激活失败"));
     }
   }, [activationCode, onActivate]);
 
+  // 离线激活：选择授权文件后由 Rust 端验证签名
+  const licenseFileInput = useRef<HTMLInputElement>(null);
+  const handleLicenseFile = useCallback(async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    e.target.value = "";
+    if (!file) return;
+
+    setIsValidating(true);
+    setError(null);
+
+    try {
+      const response = await activateLicense({
+        activationCode: "",
+        licenseFile: await file.text(),
+      });
+      setIsValidating(false);
+
+      if (response.success) {
+        setIsValid(true);
+        setTimeout(() => onActivate?.(), 2500);
+      } else {
+        setIsValid(false);
+        setError(response.message || "授权文件无效");
+      }
+    } catch (err) {
+      setIsValidating(false);
+      setIsValid(false);
+      setError(getErrorMessage(err, "离线激活失败"));
+    }
+  }, [onActivate]);
+
   return (
     <div className="activation-page">
       <TitleBar />
@@ -242,6 +269,23 @@ export function ActivationPage({ onActivate }: ActivationPageProps): React.React
                   </>
                 )}
               </button>
+
+              {/* 离线授权文件 */}
+              <input
+                ref={licenseFileInput}
+                type="file"
+                accept=".json,.lic"
+                style={{ display: "none" }}
+                onChange={handleLicenseFile}
+              />
+              <button
+                type="button"
+                className="license-file-btn"
+                onClick={() => licenseFileInput.current?.click()}
+                disabled={isValidating || isValid === true}
+              >
+                无法联网？使用离线授权文件
+              </button>
             </form>
           </div>
         </div>
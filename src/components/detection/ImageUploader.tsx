@@ -2,6 +2,12 @@ import { useRef, useEffect, useCallback } from "react";
 import type { ModalityType, ImageInfo } from "../../types";
 import type { BaseProps } from "../../types";
 import { getModalityFromFilename } from "../../utils/imageUtils";
+import {
+  getImagePreview,
+  getSupportedFormats,
+  listenFileDrop,
+  selectImageFiles,
+} from "../../api/tauri";
 
 interface ImageUploaderProps extends BaseProps {
   mode: "single" | "fusion";
@@ -12,9 +18,20 @@ interface ImageUploaderProps extends BaseProps {
   maxFiles?: number;
 }
 
+// 从本地路径中取出文件名（兼容 Windows 路径分隔符）
+function fileNameOf(path: string): string {
+  return path.split(/[\\/]/).pop() || path;
+}
+
+function extensionOf(path: string): string {
+  const name = fileNameOf(path);
+  const dotIndex = name.lastIndexOf(".");
+  return dotIndex === -1 ? "" : name.substring(dotIndex + 1).toLowerCase();
+}
+
 /**
  * 图片上传组件
- * 支持拖拽上传和点击上传
+ * 支持拖拽上传和点击上传（通过系统对话框选择，只记录本地路径，图片由 Rust 端读取）
  * 单模态模式：上传RGB或IR图片
  * 融合模式：成对上传RGB和IR图片
  */
@@ -27,72 +44,95 @@ export function ImageUploader({
   maxFiles = 50,
   className = "",
 }: ImageUploaderProps): React.ReactElement {
-  const fileInputRef = useRef<HTMLInputElement>(null);
-
-  // 当 images 为空时重置文件输入框，确保可以重新选择相同文件
-  useEffect(() => {
-    if (images.length === 0 && fileInputRef.current) {
-      fileInputRef.current.value = "";
-    }
-  }, [images.length]);
-
-  // 处理文件列表，转换为 ImageInfo
-  const processFiles = useCallback((files: FileList | File[]): ImageInfo[] => {
-    const fileArray = Array.from(files);
-    const currentCount = images.length;
-    const availableSlots = maxFiles - currentCount;
-
-    const newImages: ImageInfo[] = [];
-    let processed = 0;
-
-    fileArray.slice(0, availableSlots).forEach((file, index) => {
-      const reader = new FileReader();
-      reader.onload = (e) => {
+  const zoneRef = useRef<HTMLDivElement>(null);
+
+  // 处理本地路径列表，转换为 ImageInfo（跳过不支持的格式和已添加的图片）
+  const processPaths = useCallback(async (paths: string[]) => {
+    const supportedFormats = await getSupportedFormats();
+    const existing = new Set(images.map((image) => image.path));
+    const availableSlots = maxFiles - images.length;
+
+    const accepted = paths
+      .filter((path) => supportedFormats.includes(extensionOf(path)) && !existing.has(path))
+      .slice(0, Math.max(availableSlots, 0));
+    if (accepted.length === 0) return;
+
+    const newImages = await Promise.all(
+      accepted.map(async (path, index): Promise<ImageInfo> => {
+        const name = fileNameOf(path);
         const modality: ModalityType = mode === "fusion"
-          ? getModalityFromFilename(file.name)
+          ? getModalityFromFilename(name)
           : "rgb";
+        const preview = await getImagePreview(path).catch((err) => {
+          console.warn("[ImageUploader] 生成预览失败:", path, err);
+          return "";
+        });
 
-        newImages.push({
+        return {
           id: `${Date.now()}_${index}_${Math.random().toString(36).substring(2, 9)}`,
-          file,
-          preview: e.target?.result as string,
+          name,
+          path,
+          preview,
           modality,
-        });
-
-        processed++;
-        // 所有文件处理完成后一次性添加
-        if (processed === Math.min(fileArray.length, availableSlots)) {
-          onImagesAdd(newImages);
-        }
-      };
-      reader.readAsDataURL(file);
-    });
-
-    return newImages;
-  }, [images.length, maxFiles, mode, onImagesAdd]);
+        };
+      })
+    );
 
-  const handleFileChange = useCallback((event: React.ChangeEvent<HTMLInputElement>) => {
-    const files = event.target.files;
-    if (!files || files.length === 0) return;
-    processFiles(files);
-  }, [processFiles]);
+    // 所有文件处理完成后一次性添加
+    onImagesAdd(newImages);
+  }, [images, maxFiles, mode, onImagesAdd]);
 
-  const handleDrop = useCallback((event: React.DragEvent<HTMLDivElement>) => {
-    event.preventDefault();
-    if (disabled) return;
-
-    const imageFiles = Array.from(event.dataTransfer.files).filter(
-      (file) => file.type.startsWith("image/")
-    );
+  // 拖放监听只注册一次，通过 ref 读取最新的处理函数和禁用状态
+  const processPathsRef = useRef(processPaths);
+  const disabledRef = useRef(disabled);
+  useEffect(() => {
+    processPathsRef.current = processPaths;
+    disabledRef.current = disabled;
+  }, [processPaths, disabled]);
 
-    if (imageFiles.length === 0) return;
-    processFiles(imageFiles);
-  }, [disabled, processFiles]);
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+
+    listenFileDrop(({ paths, position }) => {
+      const zone = zoneRef.current;
+      if (disabledRef.current || !zone) return;
+
+      // 只处理拖放到上传区域内的文件
+      const rect = zone.getBoundingClientRect();
+      const inside = position.x >= rect.left && position.x <= rect.right
+        && position.y >= rect.top && position.y <= rect.bottom;
+      if (inside) {
+        void processPathsRef.current(paths);
+      }
+    })
+      .then((fn) => {
+        if (cancelled) {
+          fn();
+        } else {
+          unlisten = fn;
+        }
+      })
+      .catch((err) => console.warn("[ImageUploader] 注册拖放监听失败:", err));
 
-  const handleDragOver = useCallback((event: React.DragEvent<HTMLDivElement>) => {
-    event.preventDefault();
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
   }, []);
 
+  const handleSelectClick = useCallback(async () => {
+    if (disabled) return;
+    try {
+      const paths = await selectImageFiles(await getSupportedFormats());
+      if (paths.length > 0) {
+        await processPaths(paths);
+      }
+    } catch (err) {
+      console.error("[ImageUploader] 选择图片失败:", err);
+    }
+  }, [disabled, processPaths]);
+
   const handleRemoveClick = useCallback((id: string) => {
     onImageRemove(id);
   }, [onImageRemove]);
@@ -100,21 +140,21 @@ export function ImageUploader({
   return (
     <div className={`image-uploader ${className}`}>
       <div
+        ref={zoneRef}
         className={`upload-zone ${disabled ? "disabled" : ""}`}
-        onDrop={handleDrop}
-        onDragOver={handleDragOver}
       >
-        <input
-          ref={fileInputRef}
-          type="file"
-          accept="image/*"
-          multiple
-          onChange={handleFileChange}
-          disabled={disabled}
-          className="upload-input"
-          id="image-upload"
-        />
-        <label htmlFor="image-upload" className="upload-label">
+        <div
+          className="upload-label"
+          role="button"
+          tabIndex={disabled ? -1 : 0}
+          onClick={handleSelectClick}
+          onKeyDown={(event) => {
+            if (event.key === "Enter" || event.key === " ") {
+              event.preventDefault();
+              void handleSelectClick();
+            }
+          }}
+        >
           <div className="upload-icon">
             <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
               <path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4" />
@@ -133,14 +173,14 @@ export function ImageUploader({
           <span className="upload-count">
             已上传 {images.length} / {maxFiles} 张
           </span>
-        </label>
+        </div>
       </div>
 
       {images.length > 0 && (
         <div className="image-list">
           {images.map((image) => (
             <div key={image.id} className={`image-item ${image.modality}`}>
-              <img src={image.preview} alt={image.file.name} />
+              <img src={image.preview || undefined} alt={image.name} title={image.path} />
               <span className="image-modality">{image.modality.toUpperCase()}</span>
               <button
                 type="button"
@@ -12,6 +12,7 @@ import {
   ExportDialog,
 } from "./index";
 import { exportHistory, type ExportFormat } from "../../utils/exportUtils";
+import { getErrorMessage } from "../../api/tauri";
 import { Snackbar, Alert } from "@mui/material";
 import "../../css/App.css";
 
@@ -122,7 +123,7 @@ export function HistoryPage(): React.ReactElement {
       setNotification({
         show: true,
         success: false,
-        message: `导出失败：${error instanceof Error ? error.message : String(error)}`,
+        message: `导出失败：${getErrorMessage(error, "未知错误")}`,
       });
     }
   };
@@ -146,7 +147,7 @@ export function HistoryPage(): React.ReactElement {
       setNotification({
         show: true,
         success: false,
-        message: `删除失败：${error instanceof Error ? error.message : String(error)}`,
+        message: `删除失败：${getErrorMessage(error, "未知错误")}`,
       });
     }
   };
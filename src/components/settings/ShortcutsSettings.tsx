@@ -21,6 +21,7 @@ import {
   validateShortcut,
 } from "../../store/shortcutStore";
 import type { ShortcutActionId } from "../../types";
+import { getErrorMessage } from "../../api/tauri";
 
 /**
  * 快捷键设置组件
@@ -90,7 +91,7 @@ export function ShortcutsSettings(): React.ReactElement {
       console.log('[ShortcutsSettings] 保存成功');
     } catch (err) {
       console.error('[ShortcutsSettings] 保存失败:', err);
-      setValidationError(err instanceof Error ? err.message : '保存失败');
+      setValidationError(getErrorMessage(err, '保存失败'));
       return;
     }
 
@@ -13,16 +13,20 @@ import type {
 } from '../types';
 import {
   connectWebsocket,
-  detectSingleModeAsync,
-  detectFusionModeAsync,
+  detectSingleModePaths,
+  detectFusionModePaths,
+  pairImages,
   cancelDetection as cancelDetectionApi,
   getSupportedFormats,
+  getErrorMessage,
+  isAppError,
 } from '../api/tauri';
 import {
   registerProgressListeners,
   cleanupProgressListeners,
   waitForClientId,
   isTaskCompletedRef,
+  setCurrentTaskId,
 } from './websocketManager';
 
 // ===== 类型定义 =====
@@ -41,7 +45,6 @@ interface DetectionState {
   isConnected: boolean;
   progress: number;
   completedResults: DetectionResultItem[];
-  api: string | null;
 }
 
 interface DetectionActions {
@@ -59,6 +62,7 @@ interface DetectionActions {
 
   // WebSocket 事件
   updateProgress: (result: DetectionResultItem) => void;
+  replaceResult: (result: DetectionResultItem) => void;  // 用重试结果替换同一图片的结果
   setTaskCompleted: (status: 'completed' | 'cancelled' | 'failed') => void;
   setTaskFailed: (error: string) => void;
   setClientId: (clientId: string) => void;
@@ -79,27 +83,10 @@ const createInitialState = (): DetectionState => ({
   isConnected: false,
   progress: 0,
   completedResults: [],
-  api: null,
 });
 
 // ===== 工具函数 =====
 
-/**
- * File 转 Base64（移除 data:image/xxx;base64, 前缀）
- */
-function fileToBase64(file: File): Promise<string> {
-  return new Promise((resolve, reject) => {
-    const reader = new FileReader();
-    reader.onload = () => {
-      const result = reader.result as string;
-      const base64 = result.split(',')[1];
-      resolve(base64);
-    };
-    reader.onerror = () => reject(new Error('图片读取失败'));
-    reader.readAsDataURL(file);
-  });
-}
-
 /**
  * 检查图片格式
  */
@@ -108,9 +95,9 @@ async function checkFormat(images: ImageInfo[]): Promise<string[]> {
   const invalidImages: string[] = [];
 
   for (const img of images) {
-    const ext = img.file.name.split('.').pop()?.toLowerCase() || '';
+    const ext = img.name.split('.').pop()?.toLowerCase() || '';
     if (!supportedFormats.includes(ext)) {
-      invalidImages.push(img.file.name);
+      invalidImages.push(img.name);
     }
   }
 
@@ -118,63 +105,50 @@ async function checkFormat(images: ImageInfo[]): Promise<string[]> {
 }
 
 /**
- * 融合模式图片配对
+ * 融合模式图片配对（由 Rust 端按配置的规则配对，使用本地路径以支持按目录配对）
  */
-function pairImagesByFilename(images: ImageInfo[]): {
+async function pairFusionImages(images: ImageInfo[]): Promise<{
   pairs: Array<{ rgb: ImageInfo; ir: ImageInfo }>;
-  unpairedRgb: ImageInfo[];
-  unpairedIr: ImageInfo[];
-  invalidFiles: ImageInfo[];
-} {
-  const rgbImages = new Map<string, ImageInfo>();
-  const irImages = new Map<string, ImageInfo>();
-  const invalidFiles: ImageInfo[] = [];
-
+  errors: string[];
+}> {
+  const byName = new Map<string, ImageInfo>();
   for (const image of images) {
-    const filename = image.file.name;
-    const underscoreIndex = filename.indexOf('_');
+    byName.set(image.path, image);
+  }
 
-    if (underscoreIndex === -1) {
-      invalidFiles.push(image);
-      continue;
-    }
+  const result = await pairImages(Array.from(byName.keys()));
+  const pairs = result.pairs.map(({ rgb, ir }) => ({
+    rgb: byName.get(rgb) as ImageInfo,
+    ir: byName.get(ir) as ImageInfo,
+  }));
 
-    const prefix = filename.substring(0, underscoreIndex).toLowerCase();
-    const extIndex = filename.lastIndexOf('.');
-    const identifier = filename.substring(
-      underscoreIndex + 1,
-      extIndex > underscoreIndex ? extIndex : undefined
-    );
+  const errors: string[] = [];
+  const unrecognized = result.orphans.filter((o) => o.modality === null);
+  const unpairedRgb = result.orphans.filter((o) => o.modality === 'rgb');
+  const unpairedIr = result.orphans.filter((o) => o.modality === 'ir');
 
-    if (prefix === 'rgb') {
-      rgbImages.set(identifier, image);
-    } else if (prefix === 'ir') {
-      irImages.set(identifier, image);
-    } else {
-      invalidFiles.push(image);
-    }
+  if (unrecognized.length > 0) {
+    errors.push(
+      `以下文件无法识别 RGB/IR 模态：${unrecognized.map((o) => o.path).join(', ')}`
+    );
   }
-
-  const pairs: Array<{ rgb: ImageInfo; ir: ImageInfo }> = [];
-  const unpairedRgb: ImageInfo[] = [];
-  const unpairedIr: ImageInfo[] = [];
-
-  for (const [identifier, rgbImg] of rgbImages) {
-    const irImg = irImages.get(identifier);
-    if (irImg) {
-      pairs.push({ rgb: rgbImg, ir: irImg });
-    } else {
-      unpairedRgb.push(rgbImg);
-    }
+  if (unpairedRgb.length > 0) {
+    errors.push(
+      `以下 RGB 图片缺少对应的 IR 图片：${unpairedRgb.map((o) => o.path).join(', ')}`
+    );
   }
-
-  for (const [identifier, irImg] of irImages) {
-    if (!rgbImages.has(identifier)) {
-      unpairedIr.push(irImg);
-    }
+  if (unpairedIr.length > 0) {
+    errors.push(
+      `以下 IR 图片缺少对应的 RGB 图片：${unpairedIr.map((o) => o.path).join(', ')}`
+    );
+  }
+  for (const ambiguity of result.ambiguities) {
+    errors.push(
+      `以下图片无法唯一配对：${[...ambiguity.rgb, ...ambiguity.ir].join(', ')}`
+    );
   }
 
-  return { pairs, unpairedRgb, unpairedIr, invalidFiles };
+  return { pairs, errors };
 }
 
 // ===== 创建 Store（使用 slice 模式）=====
@@ -240,7 +214,7 @@ const createTaskSlice = (
         throw new Error(`以下图片格式不支持：${invalidImages.join(', ')}`);
       }
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : '验证失败';
+      const errorMsg = getErrorMessage(err, '验证失败');
       set({ error: errorMsg, status: 'error' });
       console.log('[detectionStore] 图片验证失败:', errorMsg);
       return;
@@ -255,19 +229,10 @@ const createTaskSlice = (
     // 获取 clientId
     let currentClientId = clientId;
 
-    // 检查 API Key 是否存在
-    const apiKey = detectionStore.getState().api;
-    if (!apiKey) {
-      set({ error: 'API Key 不存在，请重新激活', status: 'error' });
-      return;
-    }
-
-    console.log('[detectionStore] apiKey:', apiKey ? '存在' : '空');
-
     if (!currentClientId) {
       console.log('[detectionStore] 连接 WebSocket...');
       try {
-        const connectPromise = connectWebsocket(apiKey);
+        const connectPromise = connectWebsocket();
         console.log('[detectionStore] connectWebsocket 调用开始');
         const result = await connectPromise;
         console.log('[detectionStore] connectWebsocket 返回:', result);
@@ -284,7 +249,7 @@ const createTaskSlice = (
           return;
         }
       } catch (err) {
-        const errorMsg = err instanceof Error ? err.message : '连接失败';
+        const errorMsg = getErrorMessage(err, '连接失败');
         set({ error: errorMsg, status: 'error' });
         console.log('[detectionStore] WebSocket 连接失败:', errorMsg);
         return;
@@ -299,42 +264,20 @@ const createTaskSlice = (
     try {
       let taskResponse: { task_id: string; message: string };
 
+      // 图片由 Rust 端按本地路径读取和编码，Base64 不经过 IPC
       if (mode === 'single') {
-        // 单模态检测
-        const base64Images = await Promise.all(
-          images.map((img) => fileToBase64(img.file))
-        );
-        taskResponse = await detectSingleModeAsync(
+        taskResponse = await detectSingleModePaths(
           {
             mode: 'single',
             modality: 'rgb',
-            images: base64Images,
+            imagePaths: images.map((img) => img.path),
           },
-          currentClientId,
-          apiKey
+          currentClientId
         );
       } else {
         // 融合模式检测
-        const { pairs, unpairedRgb, unpairedIr, invalidFiles } =
-          pairImagesByFilename(images);
+        const { pairs, errors } = await pairFusionImages(images);
 
-        const errors: string[] = [];
-
-        if (invalidFiles.length > 0) {
-          errors.push(
-            `以下文件命名格式不正确：${invalidFiles.map((img) => img.file.name).join(', ')}`
-          );
-        }
-        if (unpairedRgb.length > 0) {
-          errors.push(
-            `以下 RGB 图片缺少对应的 IR 图片：${unpairedRgb.map((img) => img.file.name).join(', ')}`
-          );
-        }
-        if (unpairedIr.length > 0) {
-          errors.push(
-            `以下 IR 图片缺少对应的 RGB 图片：${unpairedIr.map((img) => img.file.name).join(', ')}`
-          );
-        }
         if (pairs.length === 0) {
           errors.push('未找到可配对的 RGB 和 IR 图片');
         }
@@ -343,20 +286,12 @@ const createTaskSlice = (
           throw new Error(errors.join('; '));
         }
 
-        const pairsData = await Promise.all(
-          pairs.map(async ({ rgb, ir }) => ({
-            rgb: await fileToBase64(rgb.file),
-            ir: await fileToBase64(ir.file),
-          }))
-        );
-
-        taskResponse = await detectFusionModeAsync(
+        taskResponse = await detectFusionModePaths(
           {
             mode: 'fusion',
-            pairs: pairsData,
+            pairs: pairs.map(({ rgb, ir }) => ({ rgb: rgb.path, ir: ir.path })),
           },
-          currentClientId,
-          apiKey
+          currentClientId
         );
       }
 
@@ -364,7 +299,8 @@ const createTaskSlice = (
         throw new Error(taskResponse.message || '创建任务失败');
       }
 
-      // 检查竞态条件
+      // 处理提交返回前暂存的推送，再检查竞态条件
+      setCurrentTaskId(taskResponse.task_id);
       if (!isTaskCompletedRef()) {
         console.log('[detectionStore] 任务创建成功，taskId:', taskResponse.task_id);
         set({ taskId: taskResponse.task_id });
@@ -372,15 +308,10 @@ const createTaskSlice = (
         console.log('[detectionStore] 任务已完成，跳过设置 taskId');
       }
     } catch (err) {
-      let errorMsg = '检测失败';
-      if (err instanceof Error) {
-        errorMsg = err.message;
-      } else if (typeof err === 'string') {
-        errorMsg = err;
-      }
+      let errorMsg = getErrorMessage(err, '检测失败');
 
       if (
-        errorMsg.includes('网络请求失败') ||
+        (isAppError(err) && (err.code === 'network' || err.code === 'timeout')) ||
         errorMsg.includes('Failed to fetch') ||
         errorMsg.includes('error sending request')
       ) {
@@ -400,8 +331,7 @@ const createTaskSlice = (
     }
 
     try {
-      const apiKey = detectionStore.getState().api!;
-      await cancelDetectionApi(taskId, apiKey);
+      await cancelDetectionApi(taskId);
       console.log('[detectionStore] 取消任务成功');
       // 状态更新由 ws_task_completed 事件处理
     } catch (err) {
@@ -410,12 +340,11 @@ const createTaskSlice = (
   },
 
   reset: () => {
-    const { clientId, api } = get();
+    const { clientId } = get();
     cleanupProgressListeners();
     set({
       ...createInitialState(),
       clientId, // 保留 clientId
-      api,      // 保留 API Key
     });
   },
 });
@@ -425,7 +354,7 @@ const createWebSocketSlice = (
   set: StoreApi<DetectionStore>['setState']
 ): Pick<
   DetectionActions,
-  'updateProgress' | 'setTaskCompleted' | 'setTaskFailed' | 'setClientId'
+  'updateProgress' | 'replaceResult' | 'setTaskCompleted' | 'setTaskFailed' | 'setClientId'
 > => ({
   updateProgress: (result) => {
     set((state) => {
@@ -440,6 +369,17 @@ const createWebSocketSlice = (
       };
     });
   },
+  replaceResult: (result) => {
+    set((state) => {
+      const index = state.completedResults.findIndex((r) => r.imageIndex === result.imageIndex);
+      if (index === -1) {
+        return { completedResults: [...state.completedResults, result] };
+      }
+      const completedResults = [...state.completedResults];
+      completedResults[index] = result;
+      return { completedResults };
+    });
+  },
   setTaskCompleted: (status) => {
     set((state) => ({
       status: status === 'cancelled' ? 'idle' : 'success',
@@ -530,6 +470,5 @@ export function getDetectionState(): Omit<DetectionState, 'completedResults'> &
     isConnected: state.isConnected,
     progress: state.progress,
     completedResults: state.completedResults,
-    api: state.api,
   };
 }
@@ -6,8 +6,8 @@
 
 import { createStore } from 'zustand';
 import { useStore } from 'zustand/react';
-import type { HistoryTaskItem, HistoryStatsResponse } from '../types';
-import { getAllHistory, getHistoryStats, deleteHistory as deleteHistoryApi } from '../api/tauri';
+import type { HistoryTaskItem, HistoryResultItem, HistoryStatsResponse } from '../types';
+import { getAllHistory, getHistoryStats, getHistoryTask, deleteHistory as deleteHistoryApi, getErrorMessage } from '../api/tauri';
 
 // ===== 类型定义 =====
 
@@ -69,6 +69,9 @@ interface HistoryActions {
   // 添加多条历史记录
   addHistories: (items: HistoryTaskItem[]) => void;
 
+  // 用重试结果替换历史记录中同一图片的结果，并重新统计
+  replaceHistoryResults: (taskId: string, results: HistoryResultItem[]) => void;
+
   // 删除单条历史记录（本地）
   deleteHistory: (taskId: string) => void;
 
@@ -165,7 +168,7 @@ export const historyStore = createStore<HistoryStore>()((set, get) => ({
     } catch (err) {
       set({
         isLoading: false,
-        error: err instanceof Error ? err.message : '加载失败',
+        error: getErrorMessage(err, '加载失败'),
       });
       console.error('[historyStore] 加载失败:', err);
     }
@@ -189,7 +192,7 @@ export const historyStore = createStore<HistoryStore>()((set, get) => ({
     } catch (err) {
       set({
         isLoading: false,
-        error: err instanceof Error ? err.message : '加载失败',
+        error: getErrorMessage(err, '加载失败'),
       });
       console.error('[historyStore] 刷新失败:', err);
     }
@@ -234,6 +237,33 @@ export const historyStore = createStore<HistoryStore>()((set, get) => ({
     }
   },
 
+  // 用重试结果替换历史记录中同一图片的结果，并重新统计
+  replaceHistoryResults: (taskId, results) => {
+    const currentItems = get().items;
+    const item = currentItems.find((i) => i.taskId === taskId);
+    if (!item?.results) {
+      console.log('[historyStore] 未找到记录，跳过更新重试结果:', taskId);
+      return;
+    }
+
+    const merged = item.results.map((r) => {
+      const retried = results.find((result) => result.imageIndex === r.imageIndex);
+      return retried ? { ...retried, mode: r.mode, modality: r.modality } : r;
+    });
+    const failedItems = merged.filter((r) => r.result === 'error').length;
+    const updated: HistoryTaskItem = {
+      ...item,
+      status: failedItems === 0 && item.status === 'partial_failure' ? 'completed' : item.status,
+      successfulItems: merged.length - failedItems,
+      failedItems,
+      realCount: merged.filter((r) => r.result === 'real').length,
+      fakeCount: merged.filter((r) => r.result === 'fake').length,
+      results: merged,
+    };
+    set({ items: currentItems.map((i) => (i.taskId === taskId ? updated : i)) });
+    console.log('[historyStore] 更新重试结果:', taskId);
+  },
+
   // 删除单条历史记录（本地）
   deleteHistory: (taskId) => {
     const currentItems = get().items;
@@ -249,7 +279,7 @@ export const historyStore = createStore<HistoryStore>()((set, get) => ({
       get().deleteHistory(taskId);
       console.log('[historyStore] 服务器删除成功:', taskId);
     } catch (err) {
-      const msg = err instanceof Error ? err.message : '删除失败';
+      const msg = getErrorMessage(err, '删除失败');
       set({ error: msg });
       console.error('[historyStore] 服务器删除失败:', err);
     }
@@ -299,11 +329,13 @@ export const historyStore = createStore<HistoryStore>()((set, get) => ({
   fetchTaskDetail: async (task) => {
     set({ isDetailLoading: true, selectedTask: null });
     try {
-      const found = get().items.find((item) => item.taskId === task.taskId);
-      set({ selectedTask: found || task });
+      // 完整详情（含每张图片的结果），已结束的任务由 Rust 端缓存
+      const detail = await getHistoryTask(task.taskId);
+      set({ selectedTask: detail });
     } catch (e) {
       console.error('[historyStore] 获取任务详情失败:', e);
-      set({ selectedTask: task });
+      const found = get().items.find((item) => item.taskId === task.taskId);
+      set({ selectedTask: found || task });
     } finally {
       set({ isDetailLoading: false });
     }
@@ -17,6 +17,7 @@ export {
   cleanupAllListeners,
   waitForClientId,
   isTaskCompletedRef,
+  setCurrentTaskId,
 } from './websocketManager';
 
 // ===== 历史记录 Store =====
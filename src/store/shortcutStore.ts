@@ -6,7 +6,7 @@
 
 import { createStore, useStore } from 'zustand';
 import type { ShortcutConfig, ShortcutActionId } from '../types';
-import { saveShortcutsConfig, getShortcutsConfig } from '../api/tauri';
+import { saveShortcutsConfig, getShortcutsConfig, getErrorMessage } from '../api/tauri';
 
 // ===== 类型定义 =====
 
@@ -65,7 +65,7 @@ export const shortcutStore = createStore<ShortcutStore>()((set) => ({
     } catch (err) {
       set({
         isLoading: false,
-        error: err instanceof Error ? err.message : '加载配置失败'
+        error: getErrorMessage(err, '加载配置失败')
       });
     }
   },
@@ -80,7 +80,7 @@ export const shortcutStore = createStore<ShortcutStore>()((set) => ({
     } catch (err) {
       set({
         isLoading: false,
-        error: err instanceof Error ? err.message : '保存配置失败'
+        error: getErrorMessage(err, '保存配置失败')
       });
     }
   },
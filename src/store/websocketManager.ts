@@ -7,8 +7,8 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { Event } from '@tauri-apps/api/event';
 import { detectionStore } from './detectionStore';
 import { historyStore } from './historyStore';
-import type { RustTaskDetectionResultItem, WsEventMessage } from '../api/tauri';
-import type { DetectionResultItem, HistoryTaskItem } from '../types'
+import type { RetrySubmittedEvent, RustTaskDetectionResultItem, WsEventMessage } from '../api/tauri';
+import type { DetectionResultItem, HistoryResultItem, HistoryTaskItem } from '../types'
 
 // 存储所有 unlisten 函数
 let unlistenFns: UnlistenFn[] = [];
@@ -17,6 +17,19 @@ let unlistenConnected: UnlistenFn | null = null;
 // 竞态条件处理：跟踪任务是否已完成
 let isTaskCompleted = false;
 
+// 当前检测的任务 ID；提交返回前为 null，期间收到的推送先暂存
+let currentTaskId: string | null = null;
+let pendingEvents: Array<{ handler: (msg: WsEventMessage) => void; msg: WsEventMessage }> = [];
+
+// 重试失败图片的后续任务：任务 ID → 关联的原任务（不随新的检测清空，以便更新之前任务的历史记录）
+interface RetryTask {
+  originalTaskId: string;
+  attempt: number;
+  imageIndices: number[];  // 每张图片在原任务中的索引
+}
+const retryTasks = new Map<string, RetryTask>();
+let unlistenRetryFns: UnlistenFn[] = [];
+
 // 等待 clientId 的 resolve 函数
 let clientIdResolve: ((id: string) => void) | null = null;
 
@@ -42,6 +55,62 @@ function convertResult(
   };
 }
 
+/**
+ * 将检测结果转换为历史记录中的结果
+ */
+function toHistoryResult(result: DetectionResultItem, mode: string): HistoryResultItem {
+  return {
+    mode,
+    modality: undefined,
+    result: result.result,
+    confidence: result.confidence,
+    probabilities: [],
+    processingTime: result.processingTime,
+    imageIndex: result.imageIndex,
+    error: result.errorMessage,
+  };
+}
+
+/**
+ * 将重试任务的结果映射回原任务中的图片（id 按重试任务内的图片索引生成，重复收到时保持不变）
+ */
+function convertRetryResult(
+  result: RustTaskDetectionResultItem,
+  retry: RetryTask,
+  taskId: string
+): DetectionResultItem {
+  const converted = convertResult(result, result.imageIndex ?? 0, taskId);
+  converted.imageIndex =
+    result.imageIndex !== undefined ? retry.imageIndices[result.imageIndex] : undefined;
+  return converted;
+}
+
+/**
+ * 处理推送：只处理当前任务的事件，任务 ID 未知时先暂存，其他任务的事件忽略
+ */
+function forCurrentTask(handler: (msg: WsEventMessage) => void) {
+  return (event: Event<WsEventMessage>) => {
+    const wsMsg = event.payload;
+    if (currentTaskId === null) {
+      pendingEvents.push({ handler, msg: wsMsg });
+    } else if (wsMsg.taskId === currentTaskId) {
+      handler(wsMsg);
+    }
+  };
+}
+
+/**
+ * 记录提交返回的任务 ID，并处理之前暂存的该任务的推送
+ */
+export function setCurrentTaskId(taskId: string): void {
+  currentTaskId = taskId;
+  const pending = pendingEvents;
+  pendingEvents = [];
+  pending
+    .filter(({ msg }) => msg.taskId === taskId)
+    .forEach(({ handler, msg }) => handler(msg));
+}
+
 /**
  * 注册进度监听器（每次检测前调用）
  */
@@ -53,11 +122,12 @@ export async function registerProgressListeners(): Promise<void> {
 
   // 重置任务完成标记
   isTaskCompleted = false;
+  currentTaskId = null;
+  pendingEvents = [];
 
   const unlistenProgress = await listen<WsEventMessage>(
     'ws_progress',
-    (event: Event<WsEventMessage>) => {
-      const wsMsg = event.payload;
+    forCurrentTask((wsMsg) => {
       console.log('[WebSocket] 收到 ws_progress 事件:', wsMsg);
 
       // 更新进度
@@ -81,13 +151,12 @@ export async function registerProgressListeners(): Promise<void> {
       if (wsMsg.message) {
         console.log('进度:', wsMsg.message);
       }
-    }
+    })
   );
 
   const unlistenCompleted = await listen<WsEventMessage>(
     'ws_task_completed',
-    (event: Event<WsEventMessage>) => {
-      const wsMsg = event.payload;
+    forCurrentTask((wsMsg) => {
       console.log('[WebSocket] 收到 ws_task_completed 事件:', wsMsg);
       console.log('[WebSocket] taskId:', wsMsg.taskId);
       console.log('[WebSocket] status:', wsMsg.status);
@@ -120,39 +189,94 @@ export async function registerProgressListeners(): Promise<void> {
             elapsedTimeMs: completedResults.reduce((sum, r) => sum + r.processingTime, 0),
             createdAt: completedResults[0]?.timestamp || new Date().toISOString(),
             completedAt: new Date().toISOString(),
-            results: completedResults.map((r) => ({
-              mode,
-              modality: undefined,
-              result: r.result,
-              confidence: r.confidence,
-              probabilities: [],
-              processingTime: r.processingTime,
-              imageIndex: r.imageIndex,
-              error: r.errorMessage,
-            })),
+            results: completedResults.map((r) => toHistoryResult(r, mode)),
           };
           historyStore.getState().addHistory(historyItem);
         } else {
           console.log('[WebSocket] 没有已完成的结果，跳过添加历史记录');
         }
       }
-    }
+    })
   );
 
   const unlistenFailed = await listen<WsEventMessage>(
     'ws_task_failed',
-    (event: Event<WsEventMessage>) => {
-      const wsMsg = event.payload;
+    forCurrentTask((wsMsg) => {
       console.log('[WebSocket] 收到 ws_task_failed 事件:', wsMsg);
-
       detectionStore.getState().setTaskFailed(wsMsg.message || '检测失败');
-    }
+    })
   );
 
   unlistenFns = [unlistenProgress, unlistenCompleted, unlistenFailed];
   console.log('[WebSocket] 进度监听器注册完成');
 }
 
+/**
+ * 注册重试任务监听器（应用启动时调用一次）
+ * 重试任务的结果替换原任务中失败的结果：原任务是当前检测时更新检测结果，并更新原任务的历史记录
+ */
+async function registerRetryListeners(): Promise<void> {
+  unlistenRetryFns.forEach((fn) => fn());
+
+  const unlistenSubmitted = await listen<RetrySubmittedEvent>(
+    'task_retry_submitted',
+    (event: Event<RetrySubmittedEvent>) => {
+      const retry = event.payload;
+      console.log('[WebSocket] 收到 task_retry_submitted 事件:', retry);
+      retryTasks.set(retry.taskId, {
+        originalTaskId: retry.originalTaskId,
+        attempt: retry.attempt,
+        imageIndices: retry.imageIndices,
+      });
+    }
+  );
+
+  const unlistenProgress = await listen<WsEventMessage>(
+    'ws_progress',
+    (event: Event<WsEventMessage>) => {
+      const wsMsg = event.payload;
+      const retry = retryTasks.get(wsMsg.taskId);
+      if (retry && wsMsg.result && retry.originalTaskId === currentTaskId) {
+        detectionStore.getState().replaceResult(convertRetryResult(wsMsg.result, retry, wsMsg.taskId));
+      }
+    }
+  );
+
+  const unlistenCompleted = await listen<WsEventMessage>(
+    'ws_task_completed',
+    (event: Event<WsEventMessage>) => {
+      const wsMsg = event.payload;
+      const retry = retryTasks.get(wsMsg.taskId);
+      if (!retry) return;
+      retryTasks.delete(wsMsg.taskId);
+
+      const results = (wsMsg.completedResults || []).map((result) =>
+        convertRetryResult(result, retry, wsMsg.taskId)
+      );
+      if (retry.originalTaskId === currentTaskId) {
+        results.forEach((result) => detectionStore.getState().replaceResult(result));
+      }
+      historyStore.getState().replaceHistoryResults(
+        retry.originalTaskId,
+        results.map((result) => ({ ...toHistoryResult(result, 'single'), retryCount: retry.attempt }))
+      );
+      console.log('[WebSocket] 重试任务结束:', wsMsg.taskId);
+    }
+  );
+
+  const unlistenFailed = await listen<WsEventMessage>(
+    'ws_task_failed',
+    (event: Event<WsEventMessage>) => {
+      // 重试任务失败时保留原来的失败结果
+      if (retryTasks.delete(event.payload.taskId)) {
+        console.log('[WebSocket] 重试任务失败:', event.payload.message);
+      }
+    }
+  );
+
+  unlistenRetryFns = [unlistenSubmitted, unlistenProgress, unlistenCompleted, unlistenFailed];
+}
+
 /**
  * 清理进度监听器（检测完成/取消时调用）
  */
@@ -172,6 +296,8 @@ export async function registerConnectionListener(): Promise<void> {
     unlistenConnected();
   }
 
+  await registerRetryListeners();
+
   unlistenConnected = await listen<string>('ws_connected', (event: Event<string>) => {
     const clientId = event.payload;
     console.log('[WebSocket] 收到 ws_connected 事件，clientId:', clientId);
@@ -201,6 +327,8 @@ export function cleanupAllListeners(): void {
     unlistenConnected();
     unlistenConnected = null;
   }
+  unlistenRetryFns.forEach((fn) => fn());
+  unlistenRetryFns = [];
 }
 
 /**
@@ -18,8 +18,9 @@ export type DetectionStatus = "idle" | "connecting" | "detecting" | "success" |
 // 单张图片信息
 export interface ImageInfo {
   id: string;
-  file: File;
-  preview: string;
+  name: string;  // 文件名
+  path: string;  // 本地文件路径（由 Rust 端读取图片，不经过 IPC 传输 Base64）
+  preview: string;  // 预览缩略图（data URL，生成失败时为空）
   modality: ModalityType;
 }
 